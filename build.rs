@@ -0,0 +1,176 @@
+// 构建脚本 Build script
+//
+// 把课程章节 src/base_XX.rs 的源代码按函数切分，生成 $OUT_DIR/sources.rs，由 src/source.rs 嵌入到程序中
+
+#[path = "src/lexer.rs"]
+mod lexer;
+
+use std::{env, fmt::Write, fs, path::Path};
+
+use lexer::{Kind, Token};
+
+// 切分出来的一个函数，字段的含义见 src/source.rs 中的 Snippet
+struct Function {
+    name: String,
+    line: usize,
+    doc: String,
+    code: String,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/lexer.rs");
+
+    let mut chapters: Vec<_> = fs::read_dir("src")
+        .expect("cannot read src")
+        .map(|entry| entry.expect("cannot read src").path())
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            name.starts_with("base_") && name.ends_with(".rs")
+        })
+        .collect();
+    chapters.sort();
+
+    let mut generated = String::from("// 由 build.rs 生成，不要手动修改\n\n");
+    generated.push_str("static SOURCES: &[(&str, &[Snippet])] = &[\n");
+    for path in &chapters {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let id = path.file_stem().unwrap_or_default().to_string_lossy();
+        // 没有启用 feature 的章节不编译，也不需要嵌入它的源代码
+        if env::var_os(format!("CARGO_FEATURE_{}", id.to_uppercase())).is_none() {
+            continue;
+        }
+        let source = fs::read_to_string(path).expect("cannot read chapter source");
+
+        let _ = writeln!(generated, "    ({:?}, &[", id);
+        for function in functions(&source) {
+            let _ = writeln!(
+                generated,
+                "        Snippet {{ name: {:?}, line: {}, doc: {:?}, code: {:?} }},",
+                function.name, function.line, function.doc, function.code
+            );
+        }
+        generated.push_str("    ]),\n");
+    }
+    generated.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(&out_dir).join("sources.rs"), generated).expect("cannot write sources.rs");
+}
+
+/**
+ * 找出源代码中所有带函数体的函数(包括 impl 中的方法)，按出现的顺序排列
+ *
+ * code 从函数的属性(如 #[allow(unused)])开始，到函数体结束的 } 为止
+ * doc 是紧挨在 code 前面的注释，中间有空行或者注释跟在代码后面时不算
+ * 缩进的方法会去掉 code 第一行的缩进
+ */
+fn functions(source: &str) -> Vec<Function> {
+    let tokens = lexer::tokenize(source);
+    // token 的 text 都是 source 的切片，可以直接算出它在 source 中的位置
+    let offset = |token: &Token| token.text.as_ptr() as usize - source.as_ptr() as usize;
+    let is_trivia = |token: &Token| {
+        matches!(
+            token.kind,
+            Kind::Whitespace | Kind::LineComment | Kind::BlockComment
+        )
+    };
+
+    let mut functions = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != Kind::Ident || token.text != "fn" {
+            continue;
+        }
+        let mut code = tokens[i + 1..].iter().filter(|t| !is_trivia(t));
+        let Some(name) = code.next().filter(|t| t.kind == Kind::Ident) else {
+            continue;
+        };
+        let Some(end) = body_end(code) else {
+            continue;
+        };
+
+        // 函数所在行的行首，再往前包括紧挨着的属性行
+        let mut start = line_start(source, offset(token));
+        while start > 0 {
+            let previous = line_start(source, start - 1);
+            if !source[previous..start].trim_start().starts_with("#[") {
+                break;
+            }
+            start = previous;
+        }
+
+        // 从 start 往前收集注释，直到遇到代码或者空行
+        let before = tokens[..i]
+            .iter()
+            .rposition(|t| offset(t) < start)
+            .map_or(0, |p| p + 1);
+        let mut doc_start = start;
+        for (j, t) in tokens[..before].iter().enumerate().rev() {
+            match t.kind {
+                Kind::Whitespace if t.text.matches('\n').count() < 2 => {}
+                Kind::LineComment | Kind::BlockComment => {
+                    let own_line = j == 0
+                        || (tokens[j - 1].kind == Kind::Whitespace
+                            && tokens[j - 1].text.contains('\n'));
+                    if !own_line {
+                        break;
+                    }
+                    doc_start = line_start(source, offset(t));
+                }
+                _ => break,
+            }
+        }
+
+        let line_text = &source[start..];
+        let indent = &line_text[..line_text.len() - line_text.trim_start().len()];
+        functions.push(Function {
+            name: name.text.to_string(),
+            line: source[..start].matches('\n').count() + 1,
+            doc: dedent(source[doc_start..start].trim_end(), indent),
+            code: dedent(&source[start..offset(end) + 1], indent),
+        });
+    }
+    functions
+}
+
+// 跳过参数和返回值找到函数体，返回函数体结束的 }，在函数体之前遇到 ; 说明没有函数体
+fn body_end<'a, 't>(code: impl Iterator<Item = &'t Token<'a>>) -> Option<&'t Token<'a>>
+where
+    'a: 't,
+{
+    let mut depth = 0;
+    let mut in_body = false;
+    for token in code {
+        match token.text {
+            "(" | "[" => depth += 1,
+            ")" | "]" => depth -= 1,
+            ";" if depth == 0 && !in_body => return None,
+            "{" => {
+                in_body = true;
+                depth += 1;
+            }
+            "}" => {
+                depth -= 1;
+                if depth == 0 && in_body {
+                    return Some(token);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+// 去掉每行开头的 indent，没有这个缩进的行保持原样
+fn dedent(text: &str, indent: &str) -> String {
+    if indent.is_empty() {
+        return text.to_string();
+    }
+    text.split('\n')
+        .map(|line| line.strip_prefix(indent).unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,10 @@
+// lesson: base_01::variables
+// expect: E0384
+// 变量默认不可变，不能对不可变变量 x 二次赋值
+
+fn main() {
+    let x = 5;
+    println!("The value of x is: {}", x);
+    x = 6; // cannot mutate immutable variable `x`
+    println!("The value of x is: {}", x);
+}
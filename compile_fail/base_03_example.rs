@@ -0,0 +1,9 @@
+// lesson: base_03::example
+// expect: E0382
+// s1 的所有权已经转移给了 s2，再次使用 s1 会报错
+
+fn main() {
+    let s1 = String::from("value");
+    let s2 = s1;
+    println!("s1: {}, s2: {}", s1, s2);
+}
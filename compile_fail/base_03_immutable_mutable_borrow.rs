@@ -0,0 +1,11 @@
+// lesson: base_03::repeat_mutable_borrow
+// expect: E0502
+// 可变引用与不可变引用不能同时存在，r1 和 r2 在 r3 之后还要使用，就无法创建 r3
+
+fn main() {
+    let mut s = String::from("value");
+    let r1 = &s;
+    let r2 = &s;
+    let r3 = &mut s;
+    println!("{},{},{}", r1, r2, r3);
+}
@@ -0,0 +1,13 @@
+// lesson: base_03::pass_return
+// expect: E0382
+// s 的所有权已经转移进 takes_ownership，函数结束时被 drop，不能再次使用
+
+fn takes_ownership(some_string: String) {
+    println!("{}", some_string);
+}
+
+fn main() {
+    let s = String::from("value");
+    takes_ownership(s);
+    println!("再次使用 s:{}", s);
+}
@@ -0,0 +1,10 @@
+// lesson: base_03::repeat_mutable_borrow
+// expect: E0499
+// 一个可变引用在被移出作用域之前，无法创建新的可变引用
+
+fn main() {
+    let mut s = String::from("value");
+    let r1 = &mut s;
+    let r2 = &mut s;
+    println!("{},{}", r1, r2);
+}
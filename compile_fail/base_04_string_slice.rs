@@ -0,0 +1,11 @@
+// lesson: base_04::string_slice
+// expect: E0506
+// sa 被切片 sp 可变借用期间，不能给 sa 重新赋值
+
+fn main() {
+    let mut sa: String = String::from("value");
+    let sp = &mut sa[..2];
+    println!("sp 1:{}", sp);
+    sa = String::from("aaaaa");
+    println!("sp 2:{}, sa:{}", sp, sa);
+}
@@ -0,0 +1,26 @@
+// lesson: base_04::struct_example
+// expect: E0382
+// ..user1 把 user1 中 String 类型字段的所有权转移给了 user2，user1 已经无法整体使用
+
+#[derive(Debug)]
+struct User {
+    active: bool,
+    username: String,
+    email: String,
+    sign_in_count: u64,
+}
+
+fn main() {
+    let user1 = User {
+        active: true,
+        username: String::from("Tik"),
+        email: String::from("abc@gmail.com"),
+        sign_in_count: 1,
+    };
+    let user2 = User {
+        email: String::from("kk@163.com"),
+        ..user1
+    };
+    println!("{:?}", user2);
+    println!("{:?}", user1);
+}
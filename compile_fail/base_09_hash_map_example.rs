@@ -0,0 +1,16 @@
+// lesson: base_09::hash_map_example
+// expect: E0382
+// name 没有实现 Copy，插入 HashMap 时所有权转移给了 handsome_boys，之后不能再使用
+
+use std::collections::HashMap;
+
+fn main() {
+    let name = String::from("Sunface");
+    let age = 18;
+
+    let mut handsome_boys = HashMap::new();
+    handsome_boys.insert(name, age);
+
+    println!("因为过于无耻，{}已经被从帅气男孩名单中除名", name);
+    println!("{:?}", handsome_boys);
+}
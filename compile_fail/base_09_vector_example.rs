@@ -0,0 +1,10 @@
+// lesson: base_09::vector_example
+// expect: E0502
+// first 是对 v 的不可变借用，v.push 需要可变借用(可能重新分配内存，first 会指向已释放的内存)，之后再使用 first 无法通过编译
+
+fn main() {
+    let mut v = vec![1, 2, 3, 4, 5];
+    let first = &v[0];
+    v.push(6);
+    println!("第一个元素是: {}", first);
+}
@@ -0,0 +1,10 @@
+// chapter: base_01
+// hint: 变量默认不可变，需要修改的变量声明时加上 mut. Variables are immutable by default, declare x with `mut`.
+
+// TODO: 让这段代码通过编译
+fn main() {
+    let x = 5;
+    assert_eq!(x, 5);
+    x = 6;
+    assert_eq!(x, 6);
+}
@@ -0,0 +1,12 @@
+// chapter: base_02
+// hint: 函数体最后一行带分号就成了语句，返回的是 (). A trailing `;` turns the last expression into a statement.
+
+// TODO: 让 plus_one 返回 x + 1
+fn plus_one(x: i32) -> i32 {
+    x + 1;
+}
+
+fn main() {
+    assert_eq!(plus_one(5), 6);
+    assert_eq!(plus_one(-1), 0);
+}
@@ -0,0 +1,14 @@
+// chapter: base_03
+// hint: 传入 String 会转移所有权，改成借用 &String 或 &str. Passing a String moves it, borrow it instead.
+
+// TODO: 修改 calculate_length，让 s 在调用之后仍然可用
+fn calculate_length(s: String) -> usize {
+    s.len()
+}
+
+fn main() {
+    let s = String::from("hello");
+    let len = calculate_length(s);
+    assert_eq!(len, 5);
+    assert_eq!(s, "hello");
+}
@@ -0,0 +1,13 @@
+// chapter: base_04
+// hint: 用 s.find(' ') 找到第一个空格，返回 &s[..index]. Find the first space and return a slice up to it.
+
+// TODO: 返回字符串中的第一个单词，没有空格时返回整个字符串
+fn first_word(s: &str) -> &str {
+    todo!("first_word({:?})", s)
+}
+
+fn main() {
+    assert_eq!(first_word("hello world"), "hello");
+    assert_eq!(first_word("hello"), "hello");
+    assert_eq!(first_word(""), "");
+}
@@ -0,0 +1,14 @@
+// chapter: base_05
+// hint: 用 for n in 1..=limit 遍历，n % 2 == 0 时累加. Loop over 1..=limit and add the even numbers.
+
+// TODO: 计算 1 到 limit(包含)之间所有偶数的和
+fn sum_even(limit: u32) -> u32 {
+    let mut sum = 0;
+    sum
+}
+
+fn main() {
+    assert_eq!(sum_even(0), 0);
+    assert_eq!(sum_even(4), 6);
+    assert_eq!(sum_even(10), 30);
+}
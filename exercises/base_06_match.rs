@@ -0,0 +1,24 @@
+// chapter: base_06
+// hint: match 必须穷尽所有可能，补上 Coin::Quarter 分支. A match has to cover every variant.
+
+enum Coin {
+    Penny,
+    Nickel,
+    Dime,
+    Quarter,
+}
+
+// TODO: 让 match 覆盖所有的硬币，Quarter 值 25 美分
+fn value_in_cents(coin: Coin) -> u8 {
+    match coin {
+        Coin::Penny => 1,
+        Coin::Nickel => 5,
+        Coin::Dime => 10,
+    }
+}
+
+fn main() {
+    assert_eq!(value_in_cents(Coin::Penny), 1);
+    assert_eq!(value_in_cents(Coin::Dime), 10);
+    assert_eq!(value_in_cents(Coin::Quarter), 25);
+}
@@ -0,0 +1,24 @@
+// chapter: base_07
+// hint: 方法的第一个参数是 &self，可以通过 self.radius 访问字段. Methods take `&self` and read fields through it.
+
+struct Circle {
+    radius: f64,
+}
+
+impl Circle {
+    fn new(radius: f64) -> Circle {
+        Circle { radius }
+    }
+
+    // TODO: 实现 can_hold，自己的半径大于 other 的半径时返回 true
+    fn can_hold(&self, other: &Circle) -> bool {
+        todo!()
+    }
+}
+
+fn main() {
+    let big = Circle::new(3.0);
+    let small = Circle::new(1.0);
+    assert!(big.can_hold(&small));
+    assert!(!small.can_hold(&big));
+}
@@ -0,0 +1,23 @@
+// chapter: base_08
+// hint: String 没有实现 Copy，让 largest 返回 &T 而不是 T，见 base_08 的 largest_t. Return a reference instead of requiring Copy.
+
+// TODO: 让 largest 同时支持 i32 和 String
+fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
+    let mut largest = list[0];
+
+    for &item in list.iter() {
+        if item > largest {
+            largest = item;
+        }
+    }
+
+    largest
+}
+
+fn main() {
+    let numbers = vec![34, 50, 25, 100, 65];
+    assert_eq!(*largest(&numbers), 100);
+
+    let words = vec![String::from("apple"), String::from("pear"), String::from("banana")];
+    assert_eq!(largest(&words), "pear");
+}
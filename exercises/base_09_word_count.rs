@@ -0,0 +1,20 @@
+// chapter: base_09
+// hint: 用 map.entry(word).or_insert(0) 取得计数的可变引用再加一. Use the entry API to get a mutable counter.
+
+use std::collections::HashMap;
+
+// TODO: 统计每个单词出现的次数
+fn word_count(text: &str) -> HashMap<&str, u32> {
+    let mut map = HashMap::new();
+    for word in text.split_whitespace() {
+        map.insert(word, 1);
+    }
+    map
+}
+
+fn main() {
+    let counts = word_count("hello world wonderful world");
+    assert_eq!(counts["hello"], 1);
+    assert_eq!(counts["world"], 2);
+    assert_eq!(counts.len(), 3);
+}
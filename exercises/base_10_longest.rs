@@ -0,0 +1,17 @@
+// chapter: base_10
+// hint: 返回值引用的是 x 或 y，需要标注生命周期 'a. The result borrows from x or y, annotate it with a lifetime.
+
+// TODO: 给 longest 加上生命周期标注
+fn longest(x: &str, y: &str) -> &str {
+    if x.len() > y.len() {
+        x
+    } else {
+        y
+    }
+}
+
+fn main() {
+    let string1 = String::from("long string is long");
+    let string2 = String::from("xyz");
+    assert_eq!(longest(string1.as_str(), string2.as_str()), "long string is long");
+}
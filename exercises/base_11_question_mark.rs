@@ -0,0 +1,16 @@
+// chapter: base_11
+// hint: 用 ? 把 parse 的错误返回给调用方，而不是 unwrap 之后 panic. Propagate the error with `?` instead of unwrapping.
+
+use std::num::ParseIntError;
+
+// TODO: 两个数都能解析时返回它们的和，否则返回解析错误
+fn parse_sum(a: &str, b: &str) -> Result<i32, ParseIntError> {
+    let a: i32 = a.parse().unwrap();
+    let b: i32 = b.parse().unwrap();
+    Ok(a + b)
+}
+
+fn main() {
+    assert_eq!(parse_sum("1", "2"), Ok(3));
+    assert!(parse_sum("1", "two").is_err());
+}
@@ -1,27 +1,34 @@
 // 变量
-pub fn variables() {
+
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
+pub fn variables(out: &mut Output) {
+    // 无法编译的反例，见 compile_fail/base_01_variables.rs
     // let x = 5;
     // println!("The value of x is: {}", x);
     // x = 6; // cannot mutate immutable variable `x`
     // println!("The value of x is: {}", x);
 
     let mut x = 5;
-    println!("The value of x is: {}", x);
+    outln!(out, "{}", tr!("base_01.value_of_x", x));
     x = 6;
-    println!("The value of x is: {}", x);
+    outln!(out, "{}", tr!("base_01.value_of_x", x));
 }
 
 // 未使用常量警告，用下划线开头忽略
-pub fn variable_unused_warn() {
+pub fn variable_unused_warn(_out: &mut Output) {
     let _x = 5;
     // let y = 10; //  `#[warn(unused_variables)]` on by default
 }
 
 // 常量解构
-pub fn variable_destruction() {
+pub fn variable_destruction(out: &mut Output) {
     let (a, mut b): (bool, bool) = (true, false);
     // a = true,不可变; b = false，可变
-    println!("a = {:?}, b = {:?}", a, b);
+    outln!(out, "a = {:?}, b = {:?}", a, b);
 
     b = true;
     assert_eq!(a, b);
@@ -32,7 +39,7 @@ pub struct Struct {
 }
 
 // 常量解构式赋值
-pub fn variable_destruction_assign() {
+pub fn variable_destruction_assign(_out: &mut Output) {
     let (a, b, c, d, e);
     (a, b) = (1, 2);
     // 复制 5 位， c = 1, 省略中间，d = 4, _ 用于占位
@@ -44,34 +51,61 @@ pub fn variable_destruction_assign() {
 }
 
 // 变量与常量的区别
-pub fn variable_constant_discrepancy() {
+pub fn variable_constant_discrepancy(out: &mut Output) {
     // 自始至终不可变，声明时必须标注类型。
     // 常量命名全大写，单词间下划线分隔
     // 数字字面量加入下划线提高可读性，即 100,000
     const MAX_POINTS: u32 = 100_000;
-    println!("{}", MAX_POINTS);
+    outln!(out, "{}", MAX_POINTS);
 }
 
 // 变量遮蔽 允许神明同名变量，后声明覆盖前面的
-pub fn variable_shadowing() {
+pub fn variable_shadowing(out: &mut Output) {
     let x = 5;
     // 使用第一个 x + 1 赋值新的 x
     let x = x + 1;
     {
         // 只在当前作用域生效
         let x = x * 2;
-        println!("block x :{}", x);
+        outln!(out, "block x :{}", x);
     }
-    println!("x: {}", x);
+    outln!(out, "x: {}", x);
 }
 
-pub fn main() {
-    println!("========== {} ==========", "base_01: variable start");
-    variables();
-    variable_unused_warn();
-    variable_destruction();
-    variable_destruction_assign();
-    variable_constant_discrepancy();
-    variable_shadowing();
-    println!("========== {} ==========", "base_01: variable end");
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[
+    Section::new("variables", |out, _| variables(out)),
+    Section::new("variable_unused_warn", |out, _| variable_unused_warn(out)),
+    Section::new("variable_destruction", |out, _| variable_destruction(out)),
+    Section::new("variable_destruction_assign", |out, _| {
+        variable_destruction_assign(out)
+    }),
+    Section::new("variable_constant_discrepancy", |out, _| {
+        variable_constant_discrepancy(out)
+    }),
+    Section::new("variable_shadowing", |out, _| variable_shadowing(out)),
+];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_01"
+    }
+
+    fn title(&self) -> &'static str {
+        "变量 variable"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["variable", "destructuring", "constant", "shadowing"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_01.start", "base_01.end"))
+    }
 }
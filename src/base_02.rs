@@ -2,42 +2,47 @@ use std::fmt::Debug;
 
 use num::complex::Complex;
 
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
 // Base type 基础类型
-pub fn int_type() {
+pub fn int_type(out: &mut Output) {
     let a: i8 = 1;
     let b: u8 = 2;
-    println!("i8 a:{:?}, u8 b:{:?}", a, b);
+    outln!(out, "i8 a:{:?}, u8 b:{:?}", a, b);
 
     let a: i16 = 1;
     let b: u16 = 2;
-    println!("i16 a:{:?}, u16 b:{:?}", a, b);
+    outln!(out, "i16 a:{:?}, u16 b:{:?}", a, b);
 
     let a: i32 = 1;
     let b: u32 = 2;
-    println!("i32 a:{:?}, u32 b:{:?}", a, b);
+    outln!(out, "i32 a:{:?}, u32 b:{:?}", a, b);
 
     let a: i64 = 1;
     let b: u64 = 2;
-    println!("i64 a:{:?}, u64 b:{:?}", a, b);
+    outln!(out, "i64 a:{:?}, u64 b:{:?}", a, b);
 
     let a: i128 = 1;
     let b: u128 = 2;
-    println!("i128 a:{:?}, u128 b:{:?}", a, b);
+    outln!(out, "i128 a:{:?}, u128 b:{:?}", a, b);
 
     let a: isize = 1;
     let b: usize = 2;
-    println!("isize a:{:?}, usize b:{:?}", a, b);
+    outln!(out, "isize a:{:?}, usize b:{:?}", a, b);
 }
 
 // 整型溢出
-pub fn int_overflow() {
+pub fn int_overflow(out: &mut Output) {
     let a: u8 = 255;
     let b = a.wrapping_add(20);
-    println!("b:{}", b);
+    outln!(out, "b:{}", b);
 }
 
 // 浮点类型
-pub fn float_type() {
+pub fn float_type(out: &mut Output) {
     // 默认 f64, f32 单精度、 f64 双精度
     let _x = 2.0;
     let _y: f32 = 3.0;
@@ -46,102 +51,111 @@ pub fn float_type() {
     let abc: (f32, f32, f32) = (0.1, 0.2, 0.3);
     let xyz: (f64, f64, f64) = (0.1, 0.2, 0.3);
 
-    println!("abc (f32)");
-    println!("  0.1 + 0.2: {:x}", (abc.0 + abc.1).to_bits());
-    println!("        0.3: {:x}", (abc.2).to_bits());
-    println!("xyz (f64)");
-    println!("  0.1 + 0.2: {:x}", (xyz.0 + xyz.1).to_bits());
-    println!("        0.3: {:x}", (xyz.2).to_bits());
+    outln!(out, "abc (f32)");
+    outln!(out, "  0.1 + 0.2: {:x}", (abc.0 + abc.1).to_bits());
+    outln!(out, "        0.3: {:x}", (abc.2).to_bits());
+    outln!(out, "xyz (f64)");
+    outln!(out, "  0.1 + 0.2: {:x}", (xyz.0 + xyz.1).to_bits());
+    outln!(out, "        0.3: {:x}", (xyz.2).to_bits());
     assert!(abc.0 + abc.1 == abc.2);
     // assert!(xyz.0 + xyz.1 == xyz.2); // f64精度更高，所以出现 panicked
 }
 
 // NaN
-pub fn float_non() {
+pub fn float_non(out: &mut Output) {
     // 所有和 NaN 交互的操作，都会返回一个 NaN，且不能比较
     let x = (-32.0_f32).sqrt();
     // assert_eq!(x, x); // panicked
 
     if x.is_nan() {
-        println!("异常数学行为");
+        outln!(out, "{}", tr!("base_02.nan"));
     }
 }
 
 // 数学运算
-pub fn number_operation() {
+pub fn number_operation(out: &mut Output) {
     let sum = 2 + 1;
     let difference = 5 - 1;
     let product = 4 * 2;
     let quotient = 25.1 / 2.4;
     let remainder = 43 % 5;
 
-    println!(
+    outln!(
+        out,
         "sum:{}, difference:{}, product:{}, quotient:{}, remainder:{}",
-        sum, difference, product, quotient, remainder
+        sum,
+        difference,
+        product,
+        quotient,
+        remainder
     );
 
     let addition = sum + difference;
-    println!("addition:{}", addition);
+    outln!(out, "addition:{}", addition);
 
     let one_million: i64 = 1_000_000;
-    println!("one_million:{}", one_million.pow(2));
+    outln!(out, "one_million:{}", one_million.pow(2));
 }
 
 // 位运算
-pub fn bit_operation() {
+pub fn bit_operation(out: &mut Output) {
     let a: i32 = 2;
     let b = 3i32;
 
-    println!("(a & b) = {}", a & b);
-    println!("(a | b) = {}", a | b);
-    println!("(a ^ b) = {}", a ^ b);
-    println!("(!b) = {}", !b);
-    println!("(a << b) = {}", a << b);
-    println!("(a >> b) = {}", a >> b);
+    outln!(out, "(a & b) = {}", a & b);
+    outln!(out, "(a | b) = {}", a | b);
+    outln!(out, "(a ^ b) = {}", a ^ b);
+    outln!(out, "(!b) = {}", !b);
+    outln!(out, "(a << b) = {}", a << b);
+    outln!(out, "(a >> b) = {}", a >> b);
 
     let mut a = a;
     a <<= b;
-    println!("(a << b) = {}", a);
+    outln!(out, "(a << b) = {}", a);
 }
 
 // 序列 range
-pub fn for_range() {
+pub fn for_range(out: &mut Output) {
     for i in 1..=5 {
-        println!("number range: {}", i);
+        outln!(out, "number range: {}", i);
     }
 
     for i in 'a'..='z' {
-        println!("string range: {}", i);
+        outln!(out, "string range: {}", i);
     }
 }
 
 // 有理数和复数 Rational numbers and complex numbers
-pub fn rational_complex() {
+pub fn rational_complex(out: &mut Output) {
     let a = Complex { re: 2.1, im: -1.2 };
     let b = Complex::new(11.1, 22.2);
     let result = a + b;
-    println!("{} + {}i", result.re, result.im);
+    outln!(out, "{} + {}i", result.re, result.im);
 }
 
 // 字符类型 Rust 字符使用了 ASCII 和 Unicode, 所以字符类型占用4字节, 字符用‘’, 字符串用 ""
-pub fn char_type() {
+pub fn char_type(out: &mut Output) {
     let c = 'z';
     let z = 'ℤ';
     let g = '国';
     let heart_eyed_cat = '😻';
-    println!("{}, {}, {}, {}", c, z, g, heart_eyed_cat);
+    outln!(out, "{}, {}, {}, {}", c, z, g, heart_eyed_cat);
 
-    println!("字符 'c' 占用内存: {}", std::mem::size_of_val(&c));
+    outln!(
+        out,
+        "{}",
+        tr!("base_02.char_size", std::mem::size_of_val(&c))
+    );
 }
 
 // 布尔类型
-pub fn bool_type() {
+pub fn bool_type(out: &mut Output) {
     let _t = true;
 
     let f: bool = false; // 使用类型标注,显式指定f的类型
 
     if f {
-        println!("这是段毫无意义的代码");
+        outln!(out, "{}", tr!("base_02.meaningless"));
     }
 }
 
@@ -154,9 +168,9 @@ fn add_with_extra(x: i32, y: i32) -> i32 {
     x + y // 表达式, 总要返回值，不能包含分号
 }
 
-pub fn statement_expression() {
+pub fn statement_expression(out: &mut Output) {
     let sum = add_with_extra(2, 4);
-    println!("sum: {}", sum);
+    outln!(out, "sum: {}", sum);
 
     // 语句
     let _a = 9;
@@ -180,9 +194,9 @@ fn fu_add(i: i32, j: i32) -> i32 {
 }
 
 // 无返回值()
-pub fn fn_report<T: Debug>(item: T) {
+pub fn fn_report<T: Debug>(out: &mut Output, item: T) {
     fu_add(1, 2);
-    println!("{:?}", item);
+    outln!(out, "{:?}", item);
 }
 
 // 显示返回 ()
@@ -202,22 +216,45 @@ pub fn fn_clear(text: &mut String) -> () {
 //     }
 // }
 
-pub fn main() {
-    println!("========== {} ==========", "base_02: base type start");
-    int_type();
-    int_overflow();
-    float_type();
-    float_non();
-    number_operation();
-    bit_operation();
-    for_range();
-    rational_complex();
-    char_type();
-    bool_type();
-    statement_expression();
-    fn_report(5);
-    fn_clear(&mut "a".to_string());
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[
+    Section::new("int_type", |out, _| int_type(out)),
+    Section::new("int_overflow", |out, _| int_overflow(out)),
+    Section::new("float_type", |out, _| float_type(out)),
+    Section::new("float_non", |out, _| float_non(out)),
+    Section::new("number_operation", |out, _| number_operation(out)),
+    Section::new("bit_operation", |out, _| bit_operation(out)),
+    Section::new("for_range", |out, _| for_range(out)),
+    Section::new("rational_complex", |out, _| rational_complex(out)),
+    Section::new("char_type", |out, _| char_type(out)),
+    Section::new("bool_type", |out, _| bool_type(out)),
+    Section::new("statement_expression", |out, _| statement_expression(out)),
+    Section::new("fn_report", |out, _| fn_report(out, 5)),
+    Section::new("fn_clear", |_, _| fn_clear(&mut "a".to_string())),
     // fn_dead_end();
     // fn_forever();
-    println!("========== {} ==========", "base_02: base type end");
+];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_02"
+    }
+
+    fn title(&self) -> &'static str {
+        "基础类型 base type"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["type", "number", "char", "bool", "function"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_02.start", "base_02.end"))
+    }
 }
@@ -1,40 +1,48 @@
 // 所有权和借用 Ownership and borrowing
-pub fn example() {
+
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
+pub fn example(out: &mut Output) {
     // 基础类型只是借用，在栈中存储的数据类型且存在Copy特性，在变量被赋给新变量时，仍然可用
     // 任何基本类型的组合可以 Copy ，不需要分配内存或某种形式资源的类型是可以 Copy 的
     let x = 1;
     let y = x;
-    println!("{}, {}", x, y);
+    outln!(out, "{}, {}", x, y);
 
     // 复杂类型是所有权的转移
     let s1 = String::from("value");
     let s2 = s1;
+    // 无法编译的反例，见 compile_fail/base_03_example.rs
     // println!("s1: {}, s2: {}", s1, s2); // s1 的所有权已经转移给了 s2，所以再次使用 s1 将出现异常
-    println!("s2: {}", s2);
+    outln!(out, "s2: {}", s2);
 }
 
 // 传值和返回 Pass and return values
-pub fn pass_return() {
+pub fn pass_return(out: &mut Output) {
     let s = String::from("value");
 
-    takes_ownership(s);
+    takes_ownership(out, s);
+    // 无法编译的反例，见 compile_fail/base_03_pass_return.rs
     // println!("再次使用 s:{}", s); // 出现异常，s 在takes_ownership已经被drop
 
     let x = 5;
 
-    makes_copy(x);
+    makes_copy(out, x);
 }
 
-fn takes_ownership(some_string: String) {
-    println!("{}", some_string);
+fn takes_ownership(out: &mut Output, some_string: String) {
+    outln!(out, "{}", some_string);
 }
 
-fn makes_copy(some_int: i32) {
-    println!("{}", some_int);
+fn makes_copy(out: &mut Output, some_int: i32) {
+    outln!(out, "{}", some_int);
 }
 
 // 引用与解引用
-pub fn quote() {
+pub fn quote(_out: &mut Output) {
     let x = 5;
     let y = &x;
 
@@ -43,10 +51,10 @@ pub fn quote() {
 }
 
 // 不可变引用
-pub fn immutable_quote() {
+pub fn immutable_quote(out: &mut Output) {
     let s1 = String::from("value");
     let len = calculate_length(&s1);
-    println!("{} length: {}", s1, len);
+    outln!(out, "{}", tr!("base_03.length", s1, len));
 }
 
 fn calculate_length(s: &String) -> usize {
@@ -54,27 +62,28 @@ fn calculate_length(s: &String) -> usize {
 }
 
 // 可变引用
-pub fn mutable_borrow() {
+pub fn mutable_borrow(out: &mut Output) {
     let mut s = String::from("value");
-    println!("change before:{}", s);
+    outln!(out, "{}", tr!("base_03.change_before", s));
     change_borrow_value(&mut s);
-    println!("change after:{}", s);
+    outln!(out, "{}", tr!("base_03.change_after", s));
 }
 
 fn change_borrow_value(some_string: &mut String) {
     some_string.push('a');
 }
 
-pub fn repeat_mutable_borrow() {
+pub fn repeat_mutable_borrow(out: &mut Output) {
     let mut s = String::from("value");
 
     let r1 = &mut s;
-    println!("{}", r1);
+    outln!(out, "{}", r1);
 
     let r2 = &mut s;
-    println!("{}", r2);
+    outln!(out, "{}", r2);
 
     // 一个可变引用在被移出作用域之前，无法创建新的可变引用
+    // 无法编译的反例，见 compile_fail/base_03_repeat_mutable_borrow.rs
     // let r1 = &mut s;
     // let r2 = &mut s;
     // println!("{},{}", r1, r2);
@@ -85,21 +94,45 @@ pub fn repeat_mutable_borrow() {
     let mut s = String::from("value");
 
     // 如下， 当r1 和 r2作用未结束，则无法创建r3，r3是可变引用，将改变r1，r2原本的值
+    // 无法编译的反例，见 compile_fail/base_03_immutable_mutable_borrow.rs
     let r1 = &s;
     let r2 = &s;
-    println!("{},{}", r1, r2);
+    outln!(out, "{},{}", r1, r2);
     let r3 = &mut s;
 
-    println!("{}", r3);
+    outln!(out, "{}", r3);
 }
 
-pub fn main() {
-    println!("========== {} ==========", "base_03: Ownership borrowing");
-    example();
-    pass_return();
-    quote();
-    immutable_quote();
-    mutable_borrow();
-    repeat_mutable_borrow();
-    println!("========== {} ==========", "base_03: Ownership borrowing");
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[
+    Section::new("example", |out, _| example(out)),
+    Section::new("pass_return", |out, _| pass_return(out)),
+    Section::new("quote", |out, _| quote(out)),
+    Section::new("immutable_quote", |out, _| immutable_quote(out)),
+    Section::new("mutable_borrow", |out, _| mutable_borrow(out)),
+    Section::new("repeat_mutable_borrow", |out, _| repeat_mutable_borrow(out)),
+];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_03"
+    }
+
+    fn title(&self) -> &'static str {
+        "所有权和借用 ownership borrowing"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["ownership", "borrowing", "reference"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_03.banner", "base_03.banner"))
+    }
 }
@@ -1,15 +1,20 @@
-use std::io;
+use crate::{
+    input::Input,
+    lesson::{Lesson, Section},
+    output::Output,
+};
 
 // 字符串和切片 String and slice
-pub fn string_slice() {
+pub fn string_slice(out: &mut Output) {
     let s = String::from("value");
     let va = &s[0..2];
     let lue = &s[2..5];
-    println!("s:{}, value: {}{}", s, va, lue);
+    outln!(out, "s:{}, value: {}{}", s, va, lue);
 
     let _ = &s[..];
     let _ = &s[1..];
 
+    // 无法编译的反例，见 compile_fail/base_04_string_slice.rs
     // let mut sa: String = String::from("value");
     // let sp = &mut sa[..2];
     // println!("sp 1:{}", sp);
@@ -18,50 +23,50 @@ pub fn string_slice() {
 }
 
 // 字符串和字符串字面量转换 Translate string and &str
-pub fn string_str_translate() {
+pub fn string_str_translate(out: &mut Output) {
     let s = String::from("value");
-    println!("&str: {}", &s);
-    println!("$str[..2]: {}", &s[..2]);
-    println!("String.as_str: {}", s.as_str());
+    outln!(out, "&str: {}", &s);
+    outln!(out, "$str[..2]: {}", &s[..2]);
+    outln!(out, "String.as_str: {}", s.as_str());
 }
 
 // 字符串操作 String operation
-pub fn string_operation() {
+pub fn string_operation(out: &mut Output) {
     let mut s = String::from("value ");
     s.push_str("string");
-    println!("追加字符串: push_str() -> {} ", s);
+    outln!(out, "{}", tr!("base_04.push_str", s));
 
     s.push('!');
-    println!("追加字符: push() -> {} ", s);
+    outln!(out, "{}", tr!("base_04.push", s));
 
     s.insert(5, ',');
-    println!("插入字符: insert() -> {} ", s);
+    outln!(out, "{}", tr!("base_04.insert", s));
 
     s.insert_str(6, " insert");
-    println!("插入字符串: insert_str() -> {} ", s);
+    outln!(out, "{}", tr!("base_04.insert_str", s));
 
     let mut s = s.replace("value", "VALUE");
-    println!("替换字符串: replace() -> {} ", s);
+    outln!(out, "{}", tr!("base_04.replace", s));
     // dbg!(s);
 
     s.replace_range(7..8, "I");
     // dbg!(s);
-    println!("替换字符串范围: replace_range() -> {} ", s);
+    outln!(out, "{}", tr!("base_04.replace_range", s));
 
     let pop1 = s.pop();
     let pop2 = s.pop();
-    dbg!(pop1);
-    dbg!(pop2);
-    println!("删除最后的字符: pop() -> {} ", s);
+    out_dbg!(out, pop1);
+    out_dbg!(out, pop2);
+    outln!(out, "{}", tr!("base_04.pop", s));
 
     s.remove(0);
-    println!("删除索引位置: remove() -> {} ", s);
+    outln!(out, "{}", tr!("base_04.remove", s));
 
     s.truncate(4);
-    println!("从索引开始删除到结尾: remove() -> {} ", s);
+    outln!(out, "{}", tr!("base_04.truncate", s));
 
     s.clear();
-    dbg!(s);
+    out_dbg!(out, s);
 
     let concatenate_01 = String::from("value");
     let concatenate_02 = String::from(" is string");
@@ -69,26 +74,32 @@ pub fn string_operation() {
     let mut result = result + "!";
     result += "!!!";
 
-    println!("使用 + 或 += 拼接：{}", result);
+    outln!(out, "{}", tr!("base_04.concat", result));
 
     let s1 = "hello";
     let s2 = String::from("rust");
     let s = format!("{} {}", s1, s2);
-    println!("通过格式化拼接字符串 {}", s);
+    outln!(out, "{}", tr!("base_04.format", s));
 }
 
 // 字符串转义 String transfer
-pub fn string_transfer() {
+pub fn string_transfer(out: &mut Output) {
     // 通过 \ + 字符的十六进制表示，转义输出一个字符
+    // 这里演示的就是格式字符串本身的转义，所以不放进消息目录
     let byte_escape = "I'm writing \x52\x75\x73\x74!";
-    println!("What are you doing\x3F (\\x3F means ?) {}", byte_escape);
+    outln!(
+        out,
+        "What are you doing\x3F (\\x3F means ?) {}",
+        byte_escape
+    );
 
     // \u 可以输出一个 unicode 字符
     let unicode_codepoint = "\u{211D}";
     let character_name = "\"DOUBLE_STRUCK CAPITAL R\"";
-    println!(
-        "Unicode charactre {} (U+211D) is called {}",
-        unicode_codepoint, character_name
+    outln!(
+        out,
+        "{}",
+        tr!("base_04.unicode", unicode_codepoint, character_name)
     );
 
     // 换行也保持格式、可以用 \ 忽略换行符
@@ -96,38 +107,38 @@ pub fn string_transfer() {
     can span multiple lines.
     The linebreak and indentation here ->\
     <- can be escaped too!";
-    println!("long_string: {}", long_string);
+    outln!(out, "long_string: {}", long_string);
 
     // 如果字符串包含双引号，可以在开头和结尾加 #
     let quotes = r#"And then I said: "There is no escape!""#;
-    println!("no escape: {}", quotes);
+    outln!(out, "no escape: {}", quotes);
 }
 
 // 操作 UTF-8 字符串 UTF-8 operation
-pub fn string_utf8_operation() {
+pub fn string_utf8_operation(out: &mut Output) {
     for c in "中国移动".chars() {
-        println!("char: {}", c);
+        outln!(out, "char: {}", c);
     }
 
     for b in "中国移动".bytes() {
-        println!("byte: {}", b);
+        outln!(out, "byte: {}", b);
     }
 }
 
 // 元组 tuple, 由多种类型组合，长度固定，顺序固定
-pub fn tuple_example() {
+pub fn tuple_example(out: &mut Output) {
     let tup = (500, 6.4, 1);
     let (x, y, z) = tup;
-    println!("tuple x:{}, y:{}, z:{}", x, y, z);
+    outln!(out, "tuple x:{}, y:{}, z:{}", x, y, z);
 
     let one = tup.0;
     let two = tup.1;
     let three = tup.2;
-    println!("tuple one:{}, two:{}, three:{}", one, two, three);
+    outln!(out, "tuple one:{}, two:{}, three:{}", one, two, three);
 
     let s1 = String::from("value");
     let (s2, len) = calculate_length(s1);
-    println!("{} length: {}", s2, len);
+    outln!(out, "{}", tr!("base_04.length", s2, len));
 }
 
 // 通过元组的形式返回多个值
@@ -138,11 +149,11 @@ fn calculate_length(s: String) -> (String, usize) {
 
 // 结构体 struct
 #[derive(Debug)]
-struct User {
-    active: bool,
-    username: String,
-    email: String,
-    sign_in_count: u64,
+pub struct User {
+    pub active: bool,
+    pub username: String,
+    pub email: String,
+    pub sign_in_count: u64,
 }
 
 #[derive(Debug)]
@@ -152,7 +163,7 @@ struct File {
 }
 
 // 可以类似 JS 简写同名参数
-fn build_user(username: String, email: String) -> User {
+pub fn build_user(username: String, email: String) -> User {
     User {
         active: true,
         username,
@@ -161,28 +172,33 @@ fn build_user(username: String, email: String) -> User {
     }
 }
 
-pub fn struct_example() {
+pub fn struct_example(out: &mut Output) {
     let mut user = User {
         active: true,
         username: String::from("Lisa"),
         email: String::from("value@qq.com"),
         sign_in_count: 10,
     };
-    println!(
+    outln!(
+        out,
         "username: {}, email: {}, active: {}, signCount: {}",
-        user.username, user.email, user.active, user.sign_in_count
+        user.username,
+        user.email,
+        user.active,
+        user.sign_in_count
     );
 
     user.username = String::from("Mike");
-    println!("username: {}", user.username);
+    outln!(out, "username: {}", user.username);
 
     let user1 = build_user(String::from("Tik"), String::from("abc@gmail.com"));
     let user2 = User {
         email: String::from("kk@163.com"),
         ..user1 // 类似 TS 的扩展运算符， 但是必须在尾部
     };
-    println!("{:?}", user2);
+    outln!(out, "{:?}", user2);
     // 会报错，因为 user1 中的 String 类型的参数所有权已经转移，user1 已经无法使用
+    // 无法编译的反例，见 compile_fail/base_04_struct_example.rs
     // println!("{:?}", user1);
 
     let f1 = File {
@@ -192,22 +208,22 @@ pub fn struct_example() {
 
     let f1_name = &f1.name;
     let f1_length = &f1.data.len();
-    println!("{:#?}", f1);
-    println!("{} is {} bytes long", f1_name, f1_length);
+    outln!(out, "{:#?}", f1);
+    outln!(out, "{}", tr!("base_04.bytes_long", f1_name, f1_length));
 }
 
 // 元组结构体 Tuple Struct, 结构体字段没有名称，这种结构体叫 元组结构体
-pub fn tuple_struct() {
+pub fn tuple_struct(out: &mut Output) {
     #[derive(Debug)]
     struct Color(i32, i32, i32);
     #[derive(Debug)]
     struct Point(i32, i32, i32);
 
     let black = Color(0, 0, 0);
-    println!("{:?}", black);
+    outln!(out, "{:?}", black);
 
     let origin = Point(0, 0, 0);
-    println!("{:#?}", origin);
+    outln!(out, "{:#?}", origin);
 }
 
 // 单元结构体 没有任何字段和属性
@@ -237,39 +253,40 @@ enum Message {
     ChangeColor(i32, i32, i32),
 }
 
-pub fn enum_example() {
+pub fn enum_example(out: &mut Output) {
     let heart = PokerSuit::Hearts(1);
     let diamond = PokerSuit::Diamonds(2);
-    println!("{:?}, {:?}", heart, diamond);
+    outln!(out, "{:?}, {:?}", heart, diamond);
 
     let m1 = Message::Quit;
     let m2 = Message::Move { _x: 1, _y: 2 };
     let m3 = Message::Write(String::from("value"));
     let m4 = Message::ChangeColor(99, 99, 99);
-    println!("{:?}, {:?}, {:?}, {:?}", m1, m2, m3, m4);
+    outln!(out, "{:?}, {:?}, {:?}, {:?}", m1, m2, m3, m4);
 
     let some = Some(5);
     let absent: Option<i32> = None;
-    println!("{:?}, {:?}", some, absent);
+    outln!(out, "{:?}, {:?}", some, absent);
 }
 
 // 数组 array, 长度固定、类型相同、依次排序
-pub fn array_example() {
+pub fn array_example(out: &mut Output, input: &mut Input) {
     // [T; len], T: 类型， len: 长度
     let a = [1, 2, 3, 4, 5];
-    println!("{:?}", a);
+    outln!(out, "{:?}", a);
 
     let a: [i32; 5] = [1, 2, 3, 4, 5];
-    println!("{:?}", a);
+    outln!(out, "{:?}", a);
 
     // [V:N] V值重复出现N次
     let a: [i32; 5] = [3; 5];
-    println!("{:?}", a);
+    outln!(out, "{:?}", a);
 
     let mut index = String::new();
 
-    io::stdin()
-        .read_line(&mut index)
+    // 原来是 io::stdin().read_line(&mut index)，改为从 Input 读取，无法获得输入时默认使用索引 2
+    input
+        .read_line(&mut index, "2")
         .expect("Failed to read line");
 
     let index: usize = index
@@ -278,30 +295,57 @@ pub fn array_example() {
         .expect("Index entered was not a number");
 
     let element = a[index];
-    println!("index:{} is: {}", index, element);
+    outln!(out, "{}", tr!("base_04.index", index, element));
 
     let string_arr: [String; 8] = std::array::from_fn(|_i| String::from("rust"));
-    println!("{:#?}", string_arr);
+    outln!(out, "{:#?}", string_arr);
 
     let a: [i32; 5] = [1, 2, 3, 4, 5];
     let slice: &[i32] = &a[1..3];
     assert_eq!(slice, &[2, 3]);
 }
 
-pub fn main() {
-    println!("========== {} ==========", "base_04: quote type start");
-    string_slice();
-    string_str_translate();
-    string_operation();
-    string_transfer();
-    string_utf8_operation();
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[
+    Section::new("string_slice", |out, _| string_slice(out)),
+    Section::new("string_str_translate", |out, _| string_str_translate(out)),
+    Section::new("string_operation", |out, _| string_operation(out)),
+    Section::new("string_transfer", |out, _| string_transfer(out)),
+    Section::new("string_utf8_operation", |out, _| string_utf8_operation(out)),
+    Section::new("tuple_example", |out, _| tuple_example(out)),
+    Section::new("struct_example", |out, _| struct_example(out)),
+    Section::new("tuple_struct", |out, _| tuple_struct(out)),
+    Section::new("enum_example", |out, _| enum_example(out)),
+    Section::new("array_example", array_example),
+];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_04"
+    }
 
-    tuple_example();
-    struct_example();
-    tuple_struct();
+    fn title(&self) -> &'static str {
+        "复合类型 quote type"
+    }
 
-    enum_example();
+    fn topics(&self) -> &'static [&'static str] {
+        &[
+            "string", "slice", "tuple", "struct", "enum", "array", "input",
+        ]
+    }
 
-    array_example();
-    println!("========== {} ==========", "base_04: quote type end");
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_04.start", "base_04.end"))
+    }
+
+    // array_example 需要从标准输入读取数组下标
+    fn serial(&self) -> bool {
+        true
+    }
 }
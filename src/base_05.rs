@@ -1,26 +1,31 @@
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
 // if else 无处不在
-pub fn ifelse_example() {
+pub fn ifelse_example(out: &mut Output) {
     let condition = true;
     // if else 内的返回值需要同类型
     let number = if condition { 5 } else { 6 };
 
-    println!("number: {}", number);
+    outln!(out, "number: {}", number);
 
     let n = 6;
     if n % 4 == 0 {
-        println!("divisible by 4");
+        outln!(out, "{}", tr!("base_05.divisible_by_4"));
     } else if n % 3 == 0 {
-        println!("divisible by 3");
+        outln!(out, "{}", tr!("base_05.divisible_by_3"));
     } else {
-        println!("divisible by other");
+        outln!(out, "{}", tr!("base_05.divisible_by_other"));
     }
 }
 
 // for
-pub fn for_example() {
+pub fn for_example(out: &mut Output) {
     // 从 1 到 5
     for i in 1..=5 {
-        println!("i: {}", i);
+        outln!(out, "i: {}", i);
     }
     // 需要注意所有权的转移，复合类型中数组实现了copy特性，所以可以正常使用，否则都要使用 & 引用，修改用 &mut
     // for item in collection 等价 for item in IntoIterator::into_iter(collection) 所有权转移
@@ -28,19 +33,19 @@ pub fn for_example() {
     // for item in &mut collection 等价	for item in collection.iter_mut()	可变借用
     let a = [4, 3, 2, 1];
     for (i, v) in a.iter().enumerate() {
-        println!("i:{},v:{}", i + 1, v);
+        outln!(out, "i:{},v:{}", i + 1, v);
     }
 
     // 第一种, 通过索引访问，需要进行越界检查，有性能损耗，且访问是非连续的，存在访问时数组变化的风险
     let collection = [1, 2, 3, 4, 5];
     for i in 0..collection.len() {
         let item = collection[i];
-        println!("item:{}", item);
+        outln!(out, "item:{}", item);
     }
 
     // 第二种，在编译时就分析并证明访问是安全的，且访问是连续的
     for item in collection {
-        println!("item:{}", item);
+        outln!(out, "item:{}", item);
     }
 
     // continue 跳过当次循环，开始下次循环
@@ -48,7 +53,7 @@ pub fn for_example() {
         if i == 2 {
             continue;
         }
-        println!("continue {}", i);
+        outln!(out, "continue {}", i);
     }
 
     // break 跳出整个循环，注意：只挑出当前for循环，存在嵌套时不影响上级的循环
@@ -57,25 +62,25 @@ pub fn for_example() {
             if i == 2 {
                 break;
             }
-            println!("break i {}", i);
+            outln!(out, "break i {}", i);
         }
-        println!("break j {}", j);
+        outln!(out, "break j {}", j);
     }
 }
 
 // while
-pub fn while_example() {
+pub fn while_example(out: &mut Output) {
     let mut n = 0;
 
     while n <= 5 {
-        println!("while n:{}", n);
+        outln!(out, "while n:{}", n);
         n += 1;
     }
-    println!("结束 while n");
+    outln!(out, "{}", tr!("base_05.while_end"));
 }
 
 // loop loop 是表达式，可以返回一个值， break可以带一个返回值，类似 return
-pub fn loop_example() {
+pub fn loop_example(out: &mut Output) {
     let mut counter = 0;
     let result = loop {
         counter += 1;
@@ -84,14 +89,37 @@ pub fn loop_example() {
         }
     };
 
-    println!("loop result:{}", result);
+    outln!(out, "loop result:{}", result);
 }
 
-pub fn main() {
-    println!("========== {} ==========", "base_05: flow control");
-    ifelse_example();
-    for_example();
-    while_example();
-    loop_example();
-    println!("========== {} ==========", "base_05: flow control");
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[
+    Section::new("ifelse_example", |out, _| ifelse_example(out)),
+    Section::new("for_example", |out, _| for_example(out)),
+    Section::new("while_example", |out, _| while_example(out)),
+    Section::new("loop_example", |out, _| loop_example(out)),
+];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_05"
+    }
+
+    fn title(&self) -> &'static str {
+        "流程控制 flow control"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["flow", "if", "loop"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_05.banner", "base_05.banner"))
+    }
 }
@@ -1,5 +1,10 @@
 // match
 
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
 /**
  * 通用形式如下，match也是一个表达式
  * match target {
@@ -29,13 +34,13 @@ enum Direction {
 
 #[derive(Debug)]
 #[allow(unused)]
-enum UsState {
+pub enum UsState {
     Alabama,
     Alaska,
 }
 
 #[allow(unused)]
-enum Coin {
+pub enum Coin {
     Penny,
     Nickel,
     Dime,
@@ -54,42 +59,48 @@ enum Action {
     ChangeColorRGB(u16, u16, u16),
 }
 
-fn value_in_cents(coin: Coin) -> u8 {
+fn value_in_cents(out: &mut Output, coin: Coin) -> u8 {
     match coin {
         Coin::Penny => {
-            println!("Lucky boy");
+            outln!(out, "{}", tr!("base_06.lucky"));
             1
         }
         Coin::Nickel => 5,
         Coin::Dime => 10,
         // 绑定一个值
         Coin::Quarter(state) => {
-            print!("State quarter from {:?}", state);
+            out!(
+                out,
+                "{}",
+                tr!("base_06.state_quarter", format!("{:?}", state))
+            );
             25
         }
     }
 }
 
 // match 类似 switch， _ 类似 switch 中的 default
-pub fn match_example() {
+pub fn match_example(out: &mut Output) {
     let dire = Direction::South;
     match dire {
-        Direction::East => println!("this is East"),
+        Direction::East => outln!(out, "{}", tr!("base_06.this_is_east")),
         Direction::North | Direction::South => {
-            println!("this is North or South");
+            outln!(out, "{}", tr!("base_06.north_or_south"));
         }
-        _ => println!("West"),
+        _ => outln!(out, "{}", tr!("base_06.west")),
     };
 
-    println!("{:?}", value_in_cents(Coin::Penny));
-    println!("{:?}", value_in_cents(Coin::Quarter(UsState::Alabama)));
+    let cents = value_in_cents(out, Coin::Penny);
+    outln!(out, "{:?}", cents);
+    let cents = value_in_cents(out, Coin::Quarter(UsState::Alabama));
+    outln!(out, "{:?}", cents);
 
     let ip = IpAddr::IPv6;
     let ip_str = match ip {
         IpAddr::IPv4 => "127.0.0.1",
         _ => "::1",
     };
-    println!("{}", ip_str);
+    outln!(out, "{}", ip_str);
 
     let actions = [
         Action::Say("Hello Rust".to_string()),
@@ -99,28 +110,32 @@ pub fn match_example() {
     for action in actions {
         match action {
             Action::Say(s) => {
-                println!("{}", s);
+                outln!(out, "{}", s);
             }
             Action::MoveTo(x, y) => {
-                println!("print from (0, 0) move to ({}, {})", x, y);
+                outln!(out, "{}", tr!("base_06.move_to", x, y));
             }
             Action::ChangeColorRGB(r, g, b) => {
-                println!("R:{}, G:{}, B:{}", r, g, b);
+                outln!(out, "R:{}, G:{}, B:{}", r, g, b);
             }
         }
     }
 
     match dire {
-        Direction::East => println!("East"),
-        other => println!("other direction: {:?}", other),
+        Direction::East => outln!(out, "{}", tr!("base_06.east")),
+        other => outln!(
+            out,
+            "{}",
+            tr!("base_06.other_direction", format!("{:?}", other))
+        ),
     };
 }
 
 // if let 匹配, 只匹配一个条件时且忽略其他条件时用 if let,否则都用 match
-pub fn if_let_example() {
+pub fn if_let_example(out: &mut Output) {
     let v = Some(3u8);
     if let Some(3) = v {
-        println!("three");
+        outln!(out, "{}", tr!("base_06.three"));
     }
 }
 
@@ -131,11 +146,11 @@ enum MyEnum {
     Bar,
 }
 
-pub fn matches_macro_example() {
+pub fn matches_macro_example(out: &mut Output) {
     let v = vec![MyEnum::Foo, MyEnum::Bar, MyEnum::Foo];
     // 过滤 Foo
     let v = v.iter().filter(|x| matches!(x, MyEnum::Foo));
-    println!("{:?}", v);
+    outln!(out, "{:?}", v);
 
     let foo = 'f';
     assert!(matches!(foo, 'A'..='Z' | 'a'..='z'));
@@ -149,21 +164,29 @@ pub fn matches_macro_example() {
  * 无论是 match 还是 if let，这里都是一个新的代码块，而且这里的绑定相当于新变量，如果你使用同名变量，会发生变量遮蔽
  * match中的变量遮蔽不容易看出来，所以最好不要使用同名变量，不利于理解
  */
-pub fn masking_example() {
+pub fn masking_example(out: &mut Output) {
     let age = Some(30);
-    println!("before age is {:?}", age);
+    outln!(
+        out,
+        "{}",
+        tr!("base_06.if_let_before", format!("{:?}", age))
+    );
     if let Some(age) = age {
-        println!("maching age is {:?}", age);
+        outln!(
+            out,
+            "{}",
+            tr!("base_06.if_let_matching", format!("{:?}", age))
+        );
     }
-    println!("after age is {:?}", age);
+    outln!(out, "{}", tr!("base_06.if_let_after", format!("{:?}", age)));
 
     let age = Some(31);
-    println!("在匹配前，age是{:?}", age);
+    outln!(out, "{}", tr!("base_06.match_before", format!("{:?}", age)));
     match age {
-        Some(x) => println!("匹配出来的age是{}", x),
+        Some(x) => outln!(out, "{}", tr!("base_06.match_matching", x)),
         _ => (),
     }
-    println!("在匹配后，age是{:?}", age);
+    outln!(out, "{}", tr!("base_06.match_after", format!("{:?}", age)));
 }
 
 // Option 枚举
@@ -177,26 +200,49 @@ pub fn masking_example() {
  *
  */
 
-fn plus_one(x: Option<i32>) -> Option<i32> {
+pub fn plus_one(x: Option<i32>) -> Option<i32> {
     match x {
         None => None,
         Some(i) => Some(i + 1),
     }
 }
-pub fn option_example() {
+pub fn option_example(out: &mut Output) {
     let five = Some(5);
     let six = plus_one(five);
-    println!("six {:?}", six);
+    outln!(out, "six {:?}", six);
     let none = plus_one(None);
-    println!("none {:?}", none);
+    outln!(out, "none {:?}", none);
 }
 
-pub fn main() {
-    println!("========== {} ==========", "base_06: pattern matching");
-    match_example();
-    if_let_example();
-    matches_macro_example();
-    masking_example();
-    option_example();
-    println!("========== {} ==========", "base_06: pattern matching");
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[
+    Section::new("match_example", |out, _| match_example(out)),
+    Section::new("if_let_example", |out, _| if_let_example(out)),
+    Section::new("matches_macro_example", |out, _| matches_macro_example(out)),
+    Section::new("masking_example", |out, _| masking_example(out)),
+    Section::new("option_example", |out, _| option_example(out)),
+];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_06"
+    }
+
+    fn title(&self) -> &'static str {
+        "模式匹配 pattern matching"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["pattern", "match", "option", "shadowing"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_06.banner", "base_06.banner"))
+    }
 }
@@ -1,4 +1,10 @@
 // method
+
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
 /**
  * Rust 的方法往往跟结构体、枚举、特征一起使用
  *
@@ -7,10 +13,10 @@
 
 // method define
 #[derive(Debug)]
-struct Circle {
-    x: f64,
-    y: f64,
-    radius: f64,
+pub struct Circle {
+    pub x: f64,
+    pub y: f64,
+    pub radius: f64,
 }
 
 impl Circle {
@@ -57,29 +63,54 @@ enum Message {
 }
 
 impl Message {
-    fn call(&self) {
-        println!("{:?}", &self);
+    fn call(&self, out: &mut Output) {
+        outln!(out, "{:?}", &self);
     }
 }
 
-pub fn method_example() {
+pub fn method_example(out: &mut Output) {
     let circle = Circle::new(10.0, 10.0, 5.0);
-    println!(
+    outln!(
+        out,
         "{:?}, x:{}, y:{}, radius:{}",
-        circle, circle.x, circle.y, circle.radius
+        circle,
+        circle.x,
+        circle.y,
+        circle.radius
     );
     let area = circle.area();
-    println!("area:{}", area);
+    outln!(out, "area:{}", area);
 
     let other = Circle::new(5.0, 5.0, 2.5);
-    println!("can hold:{}", circle.can_hold(&other));
+    outln!(out, "can hold:{}", circle.can_hold(&other));
 
     let msg = Message::Write(String::from("value"));
-    msg.call();
+    msg.call(out);
 }
 
-pub fn main(){
-    println!("========== {} ==========", "base_07: method");
-    method_example();
-    println!("========== {} ==========", "base_07: method");
-}
\ No newline at end of file
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[Section::new("method_example", |out, _| method_example(out))];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_07"
+    }
+
+    fn title(&self) -> &'static str {
+        "方法 method"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["method", "struct", "enum"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_07.banner", "base_07.banner"))
+    }
+}
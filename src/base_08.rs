@@ -6,19 +6,24 @@ use std::{
     ops::Add,
 };
 
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
 /**
  * 结构体中的泛型，使用同一种泛型参数的字段需要时同一类型
  * 可以存在不同的泛型参数
  */
-struct Point<T> {
-    x: T,
-    y: T,
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
 }
 
 #[derive(Debug)]
-struct Pointu<T, U> {
-    x: T,
-    y: U,
+pub struct Pointu<T, U> {
+    pub x: T,
+    pub y: U,
 }
 /**
  * 枚举中的泛型
@@ -33,7 +38,7 @@ enum Result<T, E> {
  * 结构体方法需要提前申明 imple<T>, 这样Rust才能识别Point<T>中的T时泛型，而不是具体类型
  */
 impl<T> Point<T> {
-    fn x(&self) -> &T {
+    pub fn x(&self) -> &T {
         &self.x
     }
 }
@@ -44,7 +49,7 @@ impl<T> Point<T> {
  * V，W 是方法上的泛型
  */
 impl<T, U> Pointu<T, U> {
-    fn mixup<V, W>(self, other: Pointu<V, W>) -> Pointu<T, W> {
+    pub fn mixup<V, W>(self, other: Pointu<V, W>) -> Pointu<T, W> {
         Pointu {
             x: self.x,
             y: other.y,
@@ -56,7 +61,7 @@ impl<T, U> Pointu<T, U> {
  * 为具体的泛型类型实现方法，只有 f32 类型的 Point 可以使用
  */
 impl Point<f32> {
-    fn distance_from_origin(&self) -> f32 {
+    pub fn distance_from_origin(&self) -> f32 {
         (self.x.powi(2) + self.y.powi(2)).sqrt()
     }
 }
@@ -64,8 +69,8 @@ impl Point<f32> {
 /**
  * 数组泛型
  */
-fn display_array<T: std::fmt::Debug>(arr: &[T]) {
-    println!("{:?}", arr);
+fn display_array<T: std::fmt::Debug>(out: &mut Output, arr: &[T]) {
+    outln!(out, "{:?}", arr);
 }
 
 /**
@@ -73,37 +78,37 @@ fn display_array<T: std::fmt::Debug>(arr: &[T]) {
  *
  * const 泛型表达式： const N: unsize, 表示const泛型N，它基于的值类型是 unsize
  */
-fn display_array2<T: std::fmt::Debug, const N: usize>(arr: [T; N]) {
-    println!("{:?}", arr);
+fn display_array2<T: std::fmt::Debug, const N: usize>(out: &mut Output, arr: [T; N]) {
+    outln!(out, "{:?}", arr);
 }
 
-pub fn generics_example() {
+pub fn generics_example(out: &mut Output) {
     let integer = Point { x: 5, y: 5 };
     // let float = Point { x: 1.0, y: 2.0 };
 
-    println!("{}", integer.x());
+    outln!(out, "{}", integer.x());
 
     let flex = Pointu { x: 5, y: 1.0 };
     let flex2 = Pointu { x: 5, y: 1.0 };
     let flex = flex.mixup(flex2);
 
-    println!("flex:{:?}", flex);
+    outln!(out, "flex:{:?}", flex);
 
     let f32t = Point {
         x: 1.0f32,
         y: 2.0f32,
     };
     let v = f32t.distance_from_origin();
-    println!("f32 T: {}", v);
+    outln!(out, "f32 T: {}", v);
 
     let arr = [1, 2, 3];
     let arr2 = [1, 2];
 
-    display_array(&arr);
-    display_array(&arr2);
+    display_array(out, &arr);
+    display_array(out, &arr2);
 
-    display_array2(arr);
-    display_array2(arr2);
+    display_array2(out, arr);
+    display_array2(out, arr2);
 }
 
 // 特征 Trait
@@ -117,7 +122,7 @@ pub fn generics_example() {
  * 在当前作用域中 String 可以实现 Summary 特征
  * 在当前作用域中 String 无法实现 Display 特征，因为两者都不在当前作用域中定义！！！！
  */
-trait Summary {
+pub trait Summary {
     // 没有默认实现
     fn summarize(&self) -> String;
 
@@ -128,7 +133,7 @@ trait Summary {
 }
 
 #[allow(unused)]
-struct Post {
+pub struct Post {
     pub title: String,
     pub author: String,
     pub content: String,
@@ -138,27 +143,27 @@ struct Post {
 // Post 实现 Summary 特征
 impl Summary for Post {
     fn summarize(&self) -> String {
-        format!("文章:{}, 作者:{}", self.title, self.author)
+        tr!("base_08.post_summary", self.title, self.author)
     }
 
     fn summarize_author(&self) -> String {
-        format!("作者:{}", self.author)
+        tr!("base_08.post_author", self.author)
     }
 }
 
 /**
  * 使用特征作为函数参数
  */
-fn notify(item: &impl Summary) {
-    println!("Breaking news! {}", item.summarize());
+fn notify(out: &mut Output, item: &impl Summary) {
+    outln!(out, "{}", tr!("base_08.breaking_news", item.summarize()));
 }
 
 /**
  * 上面的 notify 参数写法是语法糖，实际完整形式如下， T: Summary 被称为特征约束
  */
 #[allow(unused)]
-fn notify_1<T: Summary>(item: &T) {
-    println!("Breaking news! {}", item.summarize());
+fn notify_1<T: Summary>(out: &mut Output, item: &T) {
+    outln!(out, "{}", tr!("base_08.breaking_news", item.summarize()));
 }
 
 /**
@@ -187,13 +192,13 @@ where
 /**
  * 使用特征约束，有田间地实现方法嚯特征
  */
-struct Pair<T> {
-    x: T,
-    y: T,
+pub struct Pair<T> {
+    pub x: T,
+    pub y: T,
 }
 
 impl<T> Pair<T> {
-    fn new(x: T, y: T) -> Self {
+    pub fn new(x: T, y: T) -> Self {
         Self { x: x, y: y }
     }
 }
@@ -202,11 +207,11 @@ impl<T> Pair<T> {
  * 限制 T 实现了 Display 和 PartialOrd 才能拥有此方法
  */
 impl<T: Display + PartialOrd> Pair<T> {
-    fn cmp_display(&self) {
+    pub fn cmp_display(&self, out: &mut Output) {
         if self.x >= self.y {
-            println!("largest is x = {}", self.x);
+            outln!(out, "{}", tr!("base_08.largest_x", self.x));
         } else {
-            println!("largest is y = {}", self.y);
+            outln!(out, "{}", tr!("base_08.largest_y", self.y));
         }
     }
 }
@@ -241,7 +246,7 @@ fn returns_summarizable() -> impl Summary {
  * 堆的分配在设计大量数据时可能相当缓慢
  */
 #[allow(unused)]
-fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
+pub fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
     let mut largest = list[0];
 
     for &item in list.iter() {
@@ -257,7 +262,7 @@ fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
  * 返回引用，可以不实现 clone 或 copy 特征
  */
 #[allow(unused)]
-fn largest_t<T: PartialOrd>(list: &[T]) -> &T {
+pub fn largest_t<T: PartialOrd>(list: &[T]) -> &T {
     let mut largest = &list[0];
 
     for item in list.iter() {
@@ -278,34 +283,34 @@ fn largest_t<T: PartialOrd>(list: &[T]) -> &T {
  * 如 Copy 特征，可以调用 copy 方法，进行自我复制
  */
 
-pub fn trait_example() {
+pub fn trait_example(out: &mut Output) {
     let post = Post {
         title: "呐喊彷徨".to_string(),
         author: "鲁迅".to_string(),
         content: "".to_string(),
     };
-    println!("{}", post.summarize());
-    println!("{}", post.summarize_author());
+    outln!(out, "{}", post.summarize());
+    outln!(out, "{}", post.summarize_author());
 
-    notify(&post);
+    notify(out, &post);
 
     let pair = Pair::new(1, 2);
-    pair.cmp_display();
+    pair.cmp_display(out);
 
     let a: i32 = 10;
     let b: u16 = 100;
     let b_ = b.try_into().unwrap();
     if a < b_ {
-        println!("Ten is less than one hundred.");
+        outln!(out, "{}", tr!("base_08.ten_less"));
     }
 }
 
 // 综合例子
 #[derive(Debug)] // 为 PointAdd 结构体派生 Debug 特征，用于格式化输出
-struct PointAdd<T: Add<T, Output = T>> {
+pub struct PointAdd<T: Add<T, Output = T>> {
     // 限制类型 T 必须实现了 Add 特征，否则无法进行 + 操作
-    x: T,
-    y: T,
+    pub x: T,
+    pub y: T,
 }
 
 impl<T: Add<T, Output = T>> Add for PointAdd<T> {
@@ -323,7 +328,7 @@ fn add<T: Add<T, Output = T>>(a: T, b: T) -> T {
     a + b
 }
 
-pub fn add_example() {
+pub fn add_example(out: &mut Output) {
     let p1 = PointAdd {
         x: 1.1f32,
         y: 1.1f32,
@@ -335,12 +340,12 @@ pub fn add_example() {
 
     //println!("{:?}", add(p1, p2));
     let p1 = p1.add(p2);
-    println!("{:?}", p1);
+    outln!(out, "{:?}", p1);
 
     let p3 = PointAdd { x: 1i32, y: 1i32 };
     let p4 = PointAdd { x: 2i32, y: 2i32 };
 
-    println!("34: {:?}", add(p3, p4));
+    outln!(out, "34: {:?}", add(p3, p4));
 }
 
 // 格式化输出案例
@@ -384,20 +389,41 @@ impl File {
     }
 }
 
-pub fn display_example() {
+pub fn display_example(out: &mut Output) {
     let f6 = File::new("f6.txt");
     //...
-    println!("{:?}", f6);
-    println!("{}", f6);
+    outln!(out, "{:?}", f6);
+    outln!(out, "{}", f6);
 }
 
-pub fn main() {
-    println!("========== {} ==========", "base_08: generics and trait");
-    generics_example();
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[
+    Section::new("generics_example", |out, _| generics_example(out)),
+    Section::new("trait_example", |out, _| trait_example(out)),
+    Section::new("add_example", |out, _| add_example(out)),
+    Section::new("display_example", |out, _| display_example(out)),
+];
+
+pub struct Chapter;
 
-    trait_example();
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_08"
+    }
+
+    fn title(&self) -> &'static str {
+        "泛型和特征 generics and trait"
+    }
 
-    add_example();
-    display_example();
-    println!("========== {} ==========", "base_08: generics and trait");
+    fn topics(&self) -> &'static [&'static str] {
+        &["generics", "trait"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_08.banner", "base_08.banner"))
+    }
 }
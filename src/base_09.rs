@@ -2,7 +2,12 @@
 
 use std::collections::HashMap;
 
-pub fn vector_example() {
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
+pub fn vector_example(out: &mut Output) {
     let mut v: Vec<i32> = Vec::new();
     v.push(1);
 
@@ -18,22 +23,23 @@ pub fn vector_example() {
 
     let v = vec![1, 2, 3, 4, 5];
     let third = &v[2];
-    println!("第三个元素是:{}", third);
+    outln!(out, "{}", tr!("base_09.third", third));
 
     match v.get(2) {
-        Some(third) => println!("第三个元素是 {third}"),
-        None => println!("根本没有"),
+        Some(third) => outln!(out, "{}", tr!("base_09.third_get", third)),
+        None => outln!(out, "{}", tr!("base_09.no_third")),
     }
 
     // 如果不可变借用 first 在可变借用 v.push 后使用，无法通过编译
+    // 无法编译的反例，见 compile_fail/base_09_vector_example.rs
     let mut v = vec![1, 2, 3, 4, 5];
     let first = &v[0];
-    println!("The first element is: {first}");
+    outln!(out, "{}", tr!("base_09.first", first));
 
     v.push(6);
 
     for ele in v {
-        println!("{ele}");
+        outln!(out, "{ele}");
     }
 
     let mut c = vec![1, 2, 3, 4, 5];
@@ -41,7 +47,7 @@ pub fn vector_example() {
         *ele += 10
     }
     for ele in c {
-        println!("{ele}");
+        outln!(out, "{ele}");
     }
 
     // 存储不同类型的元素， 可以通过枚举和特征对象来实现不同类型元素的存储
@@ -58,23 +64,23 @@ pub fn vector_example() {
         IpAddr::V6("::1".to_string()),
     ];
     for ip in v {
-        println!("{:?}", ip);
+        outln!(out, "{:?}", ip);
     }
 
     // 通过特征对象
     trait IpAddrTr {
-        fn display(&self);
+        fn display(&self, out: &mut Output);
     }
     struct V4(String);
     impl IpAddrTr for V4 {
-        fn display(&self) {
-            println!("ipv4: {:?}", self.0);
+        fn display(&self, out: &mut Output) {
+            outln!(out, "ipv4: {:?}", self.0);
         }
     }
     struct V6(String);
     impl IpAddrTr for V6 {
-        fn display(&self) {
-            println!("ipv6: {:?}", self.0);
+        fn display(&self, out: &mut Output) {
+            outln!(out, "ipv6: {:?}", self.0);
         }
     }
 
@@ -83,7 +89,7 @@ pub fn vector_example() {
         Box::new(V6("::1".to_string())),
     ];
     for ip in v {
-        ip.display();
+        ip.display(out);
     }
 
     // 排序, 稳定和非稳定指的是对相等元素的处理方式
@@ -92,14 +98,14 @@ pub fn vector_example() {
     // 非稳定排序 sort_unstable 和 sort_unstable_by
     let mut v = vec![1, 3, 4, 12, 10];
     v.sort_unstable();
-    println!("{:?}", v);
+    outln!(out, "{:?}", v);
 
     let mut vec = vec![1.0, 5.6, 10.3, 2.0, 15f32];
     // 因为浮点类型并没有实现全数值比较 0rd 的特性，只实现了部分可比较 PartialOrd.
     // 所以直接排序会有问题，可以使用 partial_cmp 作为大小比较的根据
     // vec.sort_unstable();
     vec.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
-    println!("{:?}", vec);
+    outln!(out, "{:?}", vec);
 
     // 结构体排序
     #[derive(Debug)]
@@ -122,10 +128,80 @@ pub fn vector_example() {
     ];
 
     people.sort_unstable_by(|a, b| a.age.cmp(&b.age));
-    println!("people: {:?}", people);
+    outln!(out, "people: {:?}", people);
+}
+
+/**
+ * 预先分配容量 vs 逐个 push 扩容
+ * 使用 run base_09 --stats 运行，可以看到两个函数的堆分配次数和字节数的差别
+ *
+ * Vec::new 创建时不分配内存，push 时容量不够会重新分配并拷贝
+ * 每次扩容多少由标准库决定，没有保证，这里只输出 capacity >= len，重新分配的次数见 --stats
+ */
+pub fn vector_grow_example(out: &mut Output) {
+    let mut v = Vec::new();
+    for i in 0..1000 {
+        v.push(i);
+    }
+    outln!(
+        out,
+        "Vec::new, len:{}, capacity >= len:{}",
+        v.len(),
+        v.capacity() >= v.len()
+    );
+}
+
+// with_capacity 一次分配足够的内存，之后的 push 不再重新分配
+pub fn vector_capacity_example(out: &mut Output) {
+    let mut v = Vec::with_capacity(1000);
+    for i in 0..1000 {
+        v.push(i);
+    }
+    outln!(
+        out,
+        "Vec::with_capacity, len:{}, capacity:{}",
+        v.len(),
+        v.capacity()
+    );
+}
+
+// 生成一组打乱顺序的数据用于排序，简单的线性同余，保证每次运行结果一样
+fn shuffled(len: usize) -> Vec<u32> {
+    let mut seed: u32 = 42;
+    (0..len)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            seed >> 16
+        })
+        .collect()
+}
+
+/**
+ * 稳定排序 vs 非稳定排序，同样可以用 --stats 对比耗时和堆分配
+ *
+ * 稳定排序 sort 需要额外的缓冲区，数组不大时缓冲区和原数组一样大
+ * 这里 10 000 个 u32 约 40 000 字节，sort 比 sort_unstable 多分配了大约这么多
+ */
+pub fn sort_stable_example(out: &mut Output) {
+    let mut v = shuffled(10_000);
+    let before = v.len();
+    v.sort();
+    outln!(out, "{}", tr!("base_09.sort", before, v[0], v[v.len() - 1]));
+}
+
+// 非稳定排序 sort_unstable 原地排序，不分配额外空间，速度也更快
+pub fn sort_unstable_example(out: &mut Output) {
+    let mut v = shuffled(10_000);
+    let before = v.len();
+    v.sort_unstable();
+    outln!(
+        out,
+        "{}",
+        tr!("base_09.sort_unstable", before, v[0], v[v.len() - 1])
+    );
 }
 
-pub fn hash_map_example() {
+pub fn hash_map_example(out: &mut Output) {
     // 所有集合类型都是动态的，意味着没有固定的内存大小，因此底层的数据都存储在内存堆上
     let mut my_gems = HashMap::new();
     my_gems.insert("k1", 1);
@@ -145,13 +221,13 @@ pub fn hash_map_example() {
         teams_map.insert(&team.0, &team.1);
     }
 
-    println!("{:?}", teams_map);
+    outln!(out, "{:?}", teams_map);
 
     // 可以通过 into_iter 方法将列表转为迭代器，再通过 collect 方法收集
     // collect 方法内部支持生成多种类型的目标集合，所以需要 显示声明 类型
     // 如 HashMap<_, _>，即收集 HashMap 类型，KV 类型让编译器推导
     let teams_map: HashMap<_, _> = teams_list.into_iter().collect();
-    println!("{:?}", teams_map);
+    outln!(out, "{:?}", teams_map);
 
     // 所有权转移
     // 类型实现 Copy 特征， 会被复制进 HashMap，无所谓所有权
@@ -163,20 +239,21 @@ pub fn hash_map_example() {
     handsome_boys.insert(name, age);
 
     // 因为 name 的所有权转移给了 handsome_boys， 所以会下面这行会有问题
+    // 无法编译的反例，见 compile_fail/base_09_hash_map_example.rs
     // println!("因为过于无耻，{}已经被从帅气男孩名单中除名", name);
     // 因为 i32 实现了 Copy 特征， 所以仍然可以直接使用 ages
-    println!("还有，他的真实年龄远远不止{}岁", age);
+    outln!(out, "{}", tr!("base_09.real_age", age));
 
     // get 返回一个 Option<&_> 类型，如果查不到，会返回一个 None， 查询到了就是 Some<&_>
     // 返回的是对 HashMap 中值的借用，如果不使用借用，可能会发生所有权的转移
     let score = teams_map.get(&("中国队".to_string()));
-    println!("score: {:?}", score);
+    outln!(out, "score: {:?}", score);
     // copied 复制一份，unwrap 返回 i32 类型，如果前面复制的是 None，则使用 0 当默认值
     let score = teams_map.get(&("中国队".to_string())).copied().unwrap_or(0);
-    println!("score: {:?}", score);
+    outln!(out, "score: {:?}", score);
 
     for (k, v) in &teams_map {
-        println!("k:{}, v:{}", k, v);
+        outln!(out, "k:{}, v:{}", k, v);
     }
 
     let text = "hello world wonderful world";
@@ -189,12 +266,45 @@ pub fn hash_map_example() {
         *count += 1;
     }
 
-    println!("{:?}", map);
+    outln!(out, "{:?}", map);
 }
 
-pub fn main() {
-    println!("========== {} ==========", "base_09: collection");
-    vector_example();
-    hash_map_example();
-    println!("========== {} ==========", "base_09: collection");
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[
+    Section::new("vector_example", |out, _| vector_example(out)),
+    Section::new("vector_grow_example", |out, _| vector_grow_example(out)),
+    Section::new("vector_capacity_example", |out, _| {
+        vector_capacity_example(out)
+    }),
+    Section::new("sort_stable_example", |out, _| sort_stable_example(out)),
+    Section::new("sort_unstable_example", |out, _| sort_unstable_example(out)),
+    Section::new("hash_map_example", |out, _| hash_map_example(out)),
+];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_09"
+    }
+
+    fn title(&self) -> &'static str {
+        "集合类型 collection"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["collection", "vector", "hashmap"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_09.banner", "base_09.banner"))
+    }
+
+    fn unordered(&self) -> &'static [&'static str] {
+        &["k:"]
+    }
 }
@@ -1,5 +1,10 @@
 // 生命周期 Life cycle
 
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
 /**
  * 生命周期标注语法
  * 生命周期标注并不会改变任何引用的实际作用域 —— 鲁迅
@@ -15,15 +20,15 @@
 #[allow(unused)]
 fn useless<'a>(first: &'a i32, second: &'a i32) {}
 
-fn longest<'a>(x: &'a str, _: &str) -> &'a str {
+pub fn longest<'a>(x: &'a str, _: &str) -> &'a str {
     x
 }
 
-pub fn life_cycle_example() {
+pub fn life_cycle_example(out: &mut Output) {
     useless(&10, &10);
 
     let c = longest(&"a", &"b ");
-    println!("c: {}", c);
+    outln!(out, "c: {}", c);
 
     let string1 = String::from("long string is long");
     let result;
@@ -31,15 +36,38 @@ pub fn life_cycle_example() {
         let string2 = String::from("xyz");
         result = longest(string1.as_str(), string2.as_str());
     }
-    println!("The longest string is {}", result);
+    outln!(out, "{}", tr!("base_10.longest", result));
 
     // 生命周期 'static 意味着能和程序活得一样久，例如字符串字面量和特征对象
     // 实在遇到解决不了的生命周期标注问题，可以尝试 T: 'static，有时候它会给你奇迹
     let _: &'static str = "我没啥优点，就是活得久，嘿嘿";
 }
 
-pub fn main() {
-    println!("========== {} ==========", "base_10: life cycle");
-    life_cycle_example();
-    println!("========== {} ==========", "base_10: life cycle");
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[Section::new("life_cycle_example", |out, _| {
+    life_cycle_example(out)
+})];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_10"
+    }
+
+    fn title(&self) -> &'static str {
+        "生命周期 life cycle"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["lifetime", "reference"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        Some(("base_10.banner", "base_10.banner"))
+    }
 }
@@ -6,11 +6,19 @@ use std::{
     net::IpAddr,
 };
 
+use crate::{
+    lesson::{Lesson, Section},
+    output::Output,
+};
+
+// 仓库根目录下的 path.txt，用绝对路径保证从任何目录运行读写的都是同一个文件
+const PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/path.txt");
+
 /**
  * 传播错误， 但是这种写法太长了
  */
-fn read_username_from_file() -> Result<String, io::Error> {
-    let f = File::open("path.txt");
+pub fn read_username_from_file() -> Result<String, io::Error> {
+    let f = File::open(PATH);
 
     let mut f = match f {
         Ok(file) => file,
@@ -29,7 +37,7 @@ fn read_username_from_file() -> Result<String, io::Error> {
  * ？ 是一个宏，等效 match
  */
 fn simple() -> Result<String, io::Error> {
-    let mut f = File::open("path.txt")?;
+    let mut f = File::open(PATH)?;
     let mut s = String::new();
     f.read_to_string(&mut s)?;
 
@@ -43,35 +51,35 @@ fn simple() -> Result<String, io::Error> {
 fn simple2() -> Result<String, io::Error> {
     let mut s = String::new();
 
-    File::open("path.txt")?.read_to_string(&mut s)?;
+    File::open(PATH)?.read_to_string(&mut s)?;
 
     Ok(s)
 }
 
 fn simple3() -> Result<String, io::Error> {
-    fs::read_to_string("path.txt")
+    fs::read_to_string(PATH)
 }
 
-pub fn main() {
+pub fn error_example(out: &mut Output) {
     // panic!("??????")
     // let v = vec![1, 2, 3];
 
     // v[99];
 
     let home: IpAddr = "127.0.0.1".parse().unwrap();
-    println!("{:?}", home);
+    outln!(out, "{:?}", home);
 
     let a = read_username_from_file();
     match a {
-        Ok(str) => println!("file content: {}", str),
-        Err(e) => println!("file open err {:?}", e),
+        Ok(str) => outln!(out, "{}", tr!("base_11.file_content", str)),
+        Err(e) => outln!(out, "{}", tr!("base_11.file_open_err", format!("{:?}", e))),
     }
 
-    let f = File::open("path.txt");
+    let f = File::open(PATH);
     let f = match f {
         Ok(file) => file,
         Err(error) => match error.kind() {
-            ErrorKind::NotFound => match File::create("path.txt") {
+            ErrorKind::NotFound => match File::create(PATH) {
                 Ok(fc) => fc,
                 Err(fce) => panic!("Problem creating the file {:?}", fce),
             },
@@ -79,9 +87,37 @@ pub fn main() {
         },
     };
 
-    println!("file: {:?}", f);
+    outln!(out, "file: {:?}", f);
+
+    outln!(out, "simple :{:?}", simple());
+    outln!(out, "simple2 :{:?}", simple2());
+    outln!(out, "simple3 :{:?}", simple3());
+}
+
+// 本章的课程函数，按顺序运行
+const SECTIONS: &[Section] = &[Section::new("error_example", |out, _| error_example(out))];
+
+pub struct Chapter;
+
+impl Lesson for Chapter {
+    fn id(&self) -> &'static str {
+        "base_11"
+    }
+
+    fn title(&self) -> &'static str {
+        "错误处理 error handling"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["error", "result", "panic", "file"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
 
-    println!("simple :{:?}", simple());
-    println!("simple2 :{:?}", simple2());
-    println!("simple3 :{:?}", simple3());
+    // 读写仓库中的 path.txt
+    fn serial(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,308 @@
+// HTML 导出 Static HTML book export
+
+use std::{
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    highlight,
+    i18n::{self, Locale},
+    input::Input,
+    lesson::{self, Lesson},
+    output::Output,
+    report::xml_escape,
+    runner::{self, Outcome},
+    source::{self, Snippet},
+};
+
+pub const STYLE: &str = "\
+body { margin: 0; font-family: -apple-system, \"Segoe UI\", \"PingFang SC\", \"Microsoft YaHei\", sans-serif; color: #222; line-height: 1.6; }
+a { color: #b7410e; text-decoration: none; }
+a:hover { text-decoration: underline; }
+.sidebar { position: fixed; top: 0; bottom: 0; left: 0; width: 260px; overflow-y: auto; padding: 16px; box-sizing: border-box; background: #f6f3ef; border-right: 1px solid #e0dbd4; }
+.sidebar .home { display: block; font-size: 1.2em; font-weight: bold; margin-bottom: 12px; }
+.sidebar ol { padding-left: 20px; margin: 0; }
+.sidebar li { margin: 4px 0; }
+.sidebar .current { font-weight: bold; color: #222; }
+main { margin-left: 260px; padding: 24px 40px; max-width: 960px; }
+.topics { color: #777; }
+.toc { background: #faf9f7; border: 1px solid #eee; padding: 8px 16px; }
+.toc h2 { font-size: 1em; margin: 4px 0; }
+section { margin-top: 32px; }
+.doc { white-space: pre-wrap; background: #fffbea; border-left: 4px solid #e6c65c; padding: 8px 12px; }
+pre { padding: 12px; overflow-x: auto; border-radius: 4px; }
+pre.code { background: #2b2b2b; color: #e6e1dc; }
+pre.output { background: #f3f3f3; border: 1px solid #e3e3e3; }
+.panic { color: #c00; }
+.kw { color: #cc7832; } .ty { color: #ffc66d; } .mac { color: #6897bb; } .str { color: #a5c261; }
+.num { color: #6897bb; } .lt { color: #d0a0e0; } .com { color: #8a8a8a; font-style: italic; }
+.pager { display: flex; justify-content: space-between; margin-top: 40px; border-top: 1px solid #eee; padding-top: 12px; }
+table { border-collapse: collapse; }
+td, th { border-bottom: 1px solid #eee; padding: 6px 12px; text-align: left; }
+";
+
+// serve 的章节页中运行按钮的脚本，POST /run/<id> 并显示返回的输出
+const RUN_SCRIPT: &str = "\
+<script>
+var button = document.getElementById('run-button');
+button.onclick = function () {
+  fetch('/run/' + button.dataset.chapter, { method: 'POST' })
+    .then(function (response) { return response.text(); })
+    .then(function (text) { document.getElementById('run-output').textContent = text; });
+};
+</script>
+";
+
+/**
+ * 把所有章节导出为静态 HTML 网站，写到 dir 目录下，返回写出的页面数
+ *
+ * index.html    目录，章节列表来自课程注册表
+ * base_XX.html  每个章节一页: 课程函数的说明(函数前的注释)、代码和运行输出，以及章节中的其他函数
+ * style.css     样式表，页面不引用任何外部资源
+ *
+ * 页面中的文字和运行输出使用当前的语言，章节需要输入时使用默认答案
+ */
+pub fn export(dir: &Path) -> io::Result<usize> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join("style.css"), STYLE)?;
+    fs::write(dir.join("index.html"), index_page())?;
+    for lesson in lesson::registry() {
+        fs::write(page_path(dir, *lesson), chapter_page(*lesson, false))?;
+    }
+    Ok(lesson::registry().len() + 1)
+}
+
+fn page_path(dir: &Path, lesson: &dyn Lesson) -> PathBuf {
+    dir.join(format!("{}.html", lesson.id()))
+}
+
+// 目录页
+pub fn index_page() -> String {
+    let mut body = String::new();
+    let _ = writeln!(body, "<h1>{}</h1>", xml_escape(&tr!("book.title")));
+    let _ = writeln!(
+        body,
+        "<table>\n<tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>",
+        xml_escape(&tr!("book.chapter")),
+        xml_escape(&tr!("book.chapter_title")),
+        xml_escape(&tr!("book.topics")),
+        xml_escape(&tr!("book.functions"))
+    );
+    for lesson in lesson::registry() {
+        let _ = writeln!(
+            body,
+            "<tr><td><a href=\"{id}.html\">{id}</a></td><td><a href=\"{id}.html\">{}</a></td><td>{}</td><td>{}</td></tr>",
+            xml_escape(lesson.title()),
+            xml_escape(&lesson.topics().join(", ")),
+            lesson.sections().len(),
+            id = lesson.id()
+        );
+    }
+    body.push_str("</table>\n");
+    page(&tr!("book.title"), None, &body)
+}
+
+/**
+ * 章节页
+ * live 为 true 时页面由 serve 提供，末尾多一个运行按钮，点击后请求 /run/<id> 重新运行本章并显示输出
+ */
+pub fn chapter_page(lesson: &'static dyn Lesson, live: bool) -> String {
+    let id = lesson.id();
+    let mut body = String::new();
+    let _ = writeln!(body, "<h1>{} {}</h1>", id, xml_escape(lesson.title()));
+    let _ = writeln!(
+        body,
+        "<p class=\"topics\">{}: {}</p>",
+        xml_escape(&tr!("book.topics")),
+        xml_escape(&lesson.topics().join(", "))
+    );
+
+    // 本章的目录
+    let helpers: Vec<&Snippet> = source::snippets(id)
+        .iter()
+        .filter(|s| !source::LESSON_METHODS.contains(&s.name))
+        .filter(|s| {
+            lesson
+                .sections()
+                .iter()
+                .all(|section| section.name != s.name)
+        })
+        .collect();
+    let _ = writeln!(
+        body,
+        "<nav class=\"toc\"><h2>{}</h2><ol>",
+        xml_escape(&tr!("book.toc"))
+    );
+    for section in lesson.sections() {
+        let _ = writeln!(
+            body,
+            "<li><a href=\"#{name}\">{name}</a></li>",
+            name = section.name
+        );
+    }
+    if !helpers.is_empty() {
+        let _ = writeln!(
+            body,
+            "<li><a href=\"#helpers\">{}</a></li>",
+            xml_escape(&tr!("book.helpers"))
+        );
+    }
+    body.push_str("</ol></nav>\n");
+
+    for section in lesson.sections() {
+        let _ = writeln!(
+            body,
+            "<section id=\"{name}\">\n<h2>{name}</h2>",
+            name = section.name
+        );
+        if let Some(snippet) = source::find(id, section.name) {
+            snippet_html(&mut body, id, snippet);
+        }
+
+        let mut buf = Vec::new();
+        let outcome =
+            runner::run_section(section, &mut Output::new(&mut buf), &mut Input::Defaults);
+        let output = String::from_utf8_lossy(&buf);
+        let _ = writeln!(body, "<h3>{}</h3>", xml_escape(&tr!("source.output")));
+        let _ = write!(body, "<pre class=\"output\">{}", xml_escape(&output));
+        if let Outcome::Panicked { message, location } = outcome {
+            let location = location.unwrap_or_default();
+            let _ = write!(
+                body,
+                "<span class=\"panic\">{}</span>",
+                xml_escape(&tr!("runner.panicked", section.name, location, message))
+            );
+        }
+        body.push_str("</pre>\n</section>\n");
+    }
+
+    if !helpers.is_empty() {
+        let _ = writeln!(
+            body,
+            "<section id=\"helpers\">\n<h2>{}</h2>",
+            xml_escape(&tr!("book.helpers"))
+        );
+        for snippet in helpers {
+            let _ = writeln!(body, "<h3>{}</h3>", snippet.name);
+            snippet_html(&mut body, id, snippet);
+        }
+        body.push_str("</section>\n");
+    }
+
+    if live {
+        let _ = writeln!(
+            body,
+            "<section id=\"run\">\n<h2>{title}</h2>\n<button id=\"run-button\" data-chapter=\"{}\">{title}</button>",
+            id,
+            title = xml_escape(&tr!("book.run"))
+        );
+        body.push_str("<pre class=\"output\" id=\"run-output\"></pre>\n</section>\n");
+        body.push_str(RUN_SCRIPT);
+    }
+
+    // 上一章、下一章
+    let position = lesson::position(id).unwrap_or_default();
+    let registry = lesson::registry();
+    body.push_str("<p class=\"pager\">");
+    match position.checked_sub(1).map(|i| registry[i]) {
+        Some(prev) => {
+            let _ = write!(
+                body,
+                "<a href=\"{id}.html\">&larr; {id}</a>",
+                id = prev.id()
+            );
+        }
+        None => body.push_str("<span></span>"),
+    }
+    if let Some(next) = registry.get(position + 1) {
+        let _ = write!(
+            body,
+            "<a href=\"{id}.html\">{id} &rarr;</a>",
+            id = next.id()
+        );
+    }
+    body.push_str("</p>\n");
+
+    page(&format!("{} {}", id, lesson.title()), Some(id), &body)
+}
+
+// 函数前的注释去掉注释符号作为说明，代码高亮显示
+fn snippet_html(body: &mut String, id: &str, snippet: &Snippet) {
+    let doc = doc_text(snippet.doc);
+    if !doc.is_empty() {
+        let _ = writeln!(body, "<div class=\"doc\">{}</div>", xml_escape(&doc));
+    }
+    let _ = writeln!(
+        body,
+        "<pre class=\"code\" title=\"src/{}.rs:{}\"><code>{}</code></pre>",
+        id,
+        snippet.line,
+        highlight::highlight_html(snippet.code)
+    );
+}
+
+/**
+ * 去掉注释符号，只留下说明文字
+ * 支持 // 和 /// 行注释，以及每行以 * 开头的 /** */ 块注释
+ */
+fn doc_text(doc: &str) -> String {
+    let mut lines = Vec::new();
+    for line in doc.lines() {
+        let line = line.trim();
+        let text = if let Some(rest) = line.strip_prefix("/**").or_else(|| line.strip_prefix("/*"))
+        {
+            rest
+        } else if let Some(rest) = line.strip_prefix("///").or_else(|| line.strip_prefix("//")) {
+            rest
+        } else if line.starts_with("*/") {
+            ""
+        } else {
+            line.strip_prefix('*').unwrap_or(line)
+        };
+        let text = text.strip_suffix("*/").unwrap_or(text).trim();
+        if !text.is_empty() || !lines.is_empty() {
+            lines.push(text);
+        }
+    }
+    lines.join("\n").trim_end().to_string()
+}
+
+// 页面的框架: 左侧是章节导航，current 为当前章节的 id
+fn page(title: &str, current: Option<&str>, body: &str) -> String {
+    let lang = match i18n::locale() {
+        Locale::Zh => "zh-CN",
+        Locale::En => "en",
+    };
+    let mut html = String::new();
+    let _ = writeln!(html, "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>", lang);
+    html.push_str("<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    let _ = writeln!(html, "<title>{}</title>", xml_escape(title));
+    html.push_str("<link rel=\"stylesheet\" href=\"style.css\">\n</head>\n<body>\n");
+
+    let _ = writeln!(
+        html,
+        "<nav class=\"sidebar\">\n<a class=\"home\" href=\"index.html\">{}</a>\n<ol>",
+        xml_escape(&tr!("book.title"))
+    );
+    for lesson in lesson::registry() {
+        let class = if current == Some(lesson.id()) {
+            " class=\"current\""
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            html,
+            "<li><a href=\"{id}.html\"{}>{id} {}</a></li>",
+            class,
+            xml_escape(lesson.title()),
+            id = lesson.id()
+        );
+    }
+    html.push_str("</ol>\n</nav>\n<main>\n");
+    html.push_str(body);
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
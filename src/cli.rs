@@ -0,0 +1,671 @@
+// 命令行参数解析和命令分发 Command line interface
+
+use std::{
+    fmt::{self, Display},
+    io,
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use crate::{
+    book, compile_fail, exercise, explain,
+    i18n::{self, Locale},
+    input::Input,
+    lesson::{self, Lesson},
+    log::{self, Filter},
+    progress, quiz,
+    runner::{self, RunOptions},
+    search, serve, shell, snapshot, watch,
+};
+
+// 帮助信息，在消息目录中(cli.usage)，按当前语言显示
+pub fn usage() -> String {
+    tr!("cli.usage")
+}
+
+pub enum Command {
+    List { functions: bool },
+    Run(RunOptions),
+    Snapshot(Vec<&'static dyn Lesson>),
+    Verify(Vec<&'static dyn Lesson>),
+    CompileFail(Vec<&'static dyn Lesson>),
+    Exercise(ExerciseCommand),
+    Quiz(QuizCommand),
+    Progress,
+    Shell,
+    Watch(Vec<&'static dyn Lesson>),
+    Explain(Vec<String>),
+    Search(String),
+    Book(PathBuf),
+    Serve(u16),
+    Help,
+}
+
+pub enum ExerciseCommand {
+    List,
+    Verify(String),
+    Next,
+}
+
+pub enum QuizCommand {
+    Ask(Vec<&'static dyn Lesson>, Input),
+    Check(Vec<&'static dyn Lesson>),
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    UnknownCommand(String),
+    UnknownOption(String),
+    UnknownChapter(String),
+    UnknownTopic(String),
+    EmptyRange(String, String),
+    UnknownLang(String),
+    MissingValue(&'static str),
+    InvalidJobs(String),
+    InvalidPort(String),
+    InvalidLog(String),
+    InputFile(PathBuf, io::Error),
+}
+
+impl CliError {
+    /**
+     * 命令或者选项写错时才需要完整的帮助信息
+     * 章节、主题、语言等取值错误时一行提示就够了，提示中已经列出了可用的取值
+     */
+    fn needs_usage(&self) -> bool {
+        matches!(
+            self,
+            CliError::UnknownCommand(_) | CliError::UnknownOption(_) | CliError::MissingValue(_)
+        )
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            CliError::UnknownCommand(cmd) => tr!("cli.unknown_command", cmd),
+            CliError::UnknownOption(opt) => tr!("cli.unknown_option", opt),
+            CliError::UnknownChapter(id) => {
+                let ids: Vec<&str> = lesson::registry().iter().map(|l| l.id()).collect();
+                tr!("cli.unknown_chapter", id, ids.join(", "))
+            }
+            CliError::UnknownTopic(topic) => {
+                tr!("cli.unknown_topic", topic, lesson::topics().join(", "))
+            }
+            CliError::EmptyRange(from, to) => tr!("cli.empty_range", from, to),
+            CliError::UnknownLang(lang) => tr!("cli.unknown_lang", lang),
+            CliError::MissingValue(flag) => tr!("cli.missing_value", flag),
+            CliError::InvalidJobs(value) => tr!("cli.invalid_jobs", value),
+            CliError::InvalidPort(value) => tr!("cli.invalid_port", value),
+            CliError::InvalidLog(value) => tr!("cli.invalid_log", value),
+            CliError::InputFile(path, e) => tr!("cli.cannot_read", path.display(), e),
+        };
+        write!(f, "{}", message)
+    }
+}
+
+/**
+ * 全局选项
+ *
+ * --lang、--log、--timestamps 可以出现在任意位置
+ * -v、-q、--verbose、--quiet 只能写在命令之前，如 rust-learn -vv run base_01，
+ * 命令之后的同名参数属于命令本身，如 search -q 中的 -q 是搜索词，quiz --answer -v 中的 -v 是答案
+ */
+#[derive(Default)]
+struct Globals {
+    locale: Option<Locale>,
+    filter: Option<Filter>,
+    verbosity: i32,
+    timestamps: bool,
+}
+
+impl Globals {
+    // 切换语言并设置日志的过滤规则
+    fn apply(self) {
+        if let Some(locale) = self.locale {
+            i18n::set_locale(locale);
+        }
+        if let Some(filter) = self.filter {
+            log::set_filter(filter);
+        }
+        if self.verbosity != 0 {
+            log::adjust(self.verbosity);
+        }
+        if self.timestamps {
+            log::set_timestamps(true);
+        }
+    }
+}
+
+/**
+ * 解析命令行参数，args 不包含程序名本身
+ *
+ * 先取出全局选项并立即生效，这样后面解析出错时的提示也使用指定的语言，剩下的参数再按命令解析
+ */
+pub fn parse(args: &[String]) -> Result<Command, CliError> {
+    let (globals, rest) = parse_globals(args)?;
+    globals.apply();
+    parse_command(&rest)
+}
+
+// 取出全局选项，返回全局选项和剩下的参数
+fn parse_globals(args: &[String]) -> Result<(Globals, Vec<String>), CliError> {
+    let mut globals = Globals::default();
+    let mut rest = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        // 还没有遇到命令
+        let leading = rest.is_empty();
+        if arg == "--lang" {
+            let value = iter.next().ok_or(CliError::MissingValue("--lang"))?;
+            let locale =
+                Locale::parse(value).ok_or_else(|| CliError::UnknownLang(value.clone()))?;
+            globals.locale = Some(locale);
+        } else if arg == "--log" {
+            let value = iter.next().ok_or(CliError::MissingValue("--log"))?;
+            let filter = Filter::parse(value).ok_or_else(|| CliError::InvalidLog(value.clone()))?;
+            globals.filter = Some(filter);
+        } else if arg == "--timestamps" {
+            globals.timestamps = true;
+        } else if leading && arg == "--verbose" {
+            globals.verbosity += 1;
+        } else if leading && arg == "--quiet" {
+            globals.verbosity -= 1;
+        } else if let Some(n) = repeated(arg, 'v').filter(|_| leading) {
+            globals.verbosity += n;
+        } else if let Some(n) = repeated(arg, 'q').filter(|_| leading) {
+            globals.verbosity -= n;
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    Ok((globals, rest))
+}
+
+// -v、-vv 这样重复同一个字母的短选项，返回重复的次数
+fn repeated(arg: &str, flag: char) -> Option<i32> {
+    let letters = arg.strip_prefix('-')?;
+    (!letters.is_empty() && letters.chars().all(|c| c == flag)).then_some(letters.len() as i32)
+}
+
+fn parse_command(args: &[String]) -> Result<Command, CliError> {
+    let Some(command) = args.first() else {
+        return parse_run(&[]).map(Command::Run);
+    };
+
+    match command.as_str() {
+        "list" => match args.get(1).map(String::as_str) {
+            None => Ok(Command::List { functions: false }),
+            Some("--functions") => Ok(Command::List { functions: true }),
+            Some(other) => Err(CliError::UnknownOption(other.to_string())),
+        },
+        "run" => parse_run(&args[1..]).map(Command::Run),
+        "snapshot" => select(&args[1..]).map(Command::Snapshot),
+        "verify" => select(&args[1..]).map(Command::Verify),
+        "compile-fail" => select(&args[1..]).map(Command::CompileFail),
+        "exercise" => parse_exercise(&args[1..]).map(Command::Exercise),
+        "quiz" => parse_quiz(&args[1..]).map(Command::Quiz),
+        "progress" => Ok(Command::Progress),
+        "shell" => Ok(Command::Shell),
+        "watch" => select(&args[1..]).map(Command::Watch),
+        "explain" => match &args[1..] {
+            [] => Ok(Command::Explain(Vec::new())),
+            [dash] if dash == "-" => Ok(Command::Explain(Vec::new())),
+            codes => Ok(Command::Explain(codes.to_vec())),
+        },
+        "search" => match &args[1..] {
+            [] => Err(CliError::MissingValue("search")),
+            terms => Ok(Command::Search(terms.join(" "))),
+        },
+        "serve" => match &args[1..] {
+            [] => Ok(Command::Serve(8080)),
+            [flag, port] if flag == "--port" => port
+                .parse()
+                .map(Command::Serve)
+                .map_err(|_| CliError::InvalidPort(port.clone())),
+            [flag] if flag == "--port" => Err(CliError::MissingValue("--port")),
+            [other, ..] => Err(CliError::UnknownOption(other.clone())),
+        },
+        "book" => match &args[1..] {
+            [] => Ok(Command::Book(PathBuf::from("book"))),
+            [dir] => Ok(Command::Book(PathBuf::from(dir))),
+            [_, extra, ..] => Err(CliError::UnknownOption(extra.clone())),
+        },
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        other => Err(CliError::UnknownCommand(other.to_string())),
+    }
+}
+
+fn parse_exercise(args: &[String]) -> Result<ExerciseCommand, CliError> {
+    match args.first().map(String::as_str) {
+        None | Some("list") => Ok(ExerciseCommand::List),
+        Some("verify") => match args.get(1) {
+            Some(id) => Ok(ExerciseCommand::Verify(id.clone())),
+            None => Err(CliError::MissingValue("exercise verify")),
+        },
+        Some("next") => Ok(ExerciseCommand::Next),
+        Some(other) => Err(CliError::UnknownCommand(format!("exercise {}", other))),
+    }
+}
+
+// quiz check 之外的参数中可以用 --answer 预先给出答案，其余的用于挑选章节
+fn parse_quiz(args: &[String]) -> Result<QuizCommand, CliError> {
+    if args.first().is_some_and(|arg| arg == "check") {
+        return select(&args[1..]).map(QuizCommand::Check);
+    }
+
+    let mut answers = Vec::new();
+    let mut rest = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--answer" {
+            answers.push(
+                iter.next()
+                    .ok_or(CliError::MissingValue("--answer"))?
+                    .clone(),
+            );
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    let input = if answers.is_empty() {
+        Input::Stdin
+    } else {
+        Input::scripted(answers)
+    };
+    Ok(QuizCommand::Ask(select(&rest)?, input))
+}
+
+// 解析 run 的参数，先取出 run 特有的选项，剩下的用于挑选章节
+fn parse_run(args: &[String]) -> Result<RunOptions, CliError> {
+    let mut output = None;
+    let mut input = Input::Stdin;
+    let mut answers = Vec::new();
+    let mut json = None;
+    let mut junit = None;
+    let mut stats = false;
+    let mut with_source = false;
+    let mut jobs = 1;
+    let mut quiz = false;
+    let mut rest = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--output" {
+            let path = iter.next().ok_or(CliError::MissingValue("--output"))?;
+            output = Some(PathBuf::from(path));
+        } else if arg == "--input" {
+            let path = PathBuf::from(iter.next().ok_or(CliError::MissingValue("--input"))?);
+            input = Input::from_file(&path).map_err(|e| CliError::InputFile(path, e))?;
+        } else if arg == "--answer" {
+            answers.push(
+                iter.next()
+                    .ok_or(CliError::MissingValue("--answer"))?
+                    .clone(),
+            );
+        } else if arg == "--no-input" {
+            input = Input::Defaults;
+        } else if arg == "--stats" {
+            stats = true;
+        } else if arg == "--with-source" {
+            with_source = true;
+        } else if arg == "--quiz" {
+            quiz = true;
+        } else if arg == "--jobs" {
+            let value = iter.next().ok_or(CliError::MissingValue("--jobs"))?;
+            jobs = value
+                .parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| CliError::InvalidJobs(value.clone()))?;
+        } else if arg == "--json" {
+            let path = iter.next().ok_or(CliError::MissingValue("--json"))?;
+            json = Some(PathBuf::from(path));
+        } else if arg == "--junit" {
+            let path = iter.next().ok_or(CliError::MissingValue("--junit"))?;
+            junit = Some(PathBuf::from(path));
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+
+    if !answers.is_empty() {
+        input = Input::scripted(answers);
+    }
+
+    Ok(RunOptions {
+        lessons: select(&rest)?,
+        output,
+        input,
+        json,
+        junit,
+        stats,
+        with_source,
+        jobs,
+        quiz,
+    })
+}
+
+/**
+ * 根据章节 id、区间和主题挑选章节，结果按课程顺序排列且不重复
+ * 没有指定任何章节时选中全部章节
+ */
+fn select(args: &[String]) -> Result<Vec<&'static dyn Lesson>, CliError> {
+    let registry = lesson::registry();
+
+    let mut picked = vec![false; registry.len()];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--topic" {
+            let topic = iter.next().ok_or(CliError::MissingValue("--topic"))?;
+            let mut found = false;
+            for (i, lesson) in registry.iter().enumerate() {
+                if lesson
+                    .topics()
+                    .iter()
+                    .any(|t| t.eq_ignore_ascii_case(topic))
+                {
+                    picked[i] = true;
+                    found = true;
+                }
+            }
+            if !found {
+                return Err(CliError::UnknownTopic(topic.to_string()));
+            }
+        } else if let Some((from, to)) = arg.split_once("..") {
+            let start = position(from)?;
+            let end = position(to)?;
+            if start > end {
+                return Err(CliError::EmptyRange(from.to_string(), to.to_string()));
+            }
+            for p in &mut picked[start..=end] {
+                *p = true;
+            }
+        } else if arg.starts_with('-') {
+            return Err(CliError::UnknownOption(arg.to_string()));
+        } else {
+            picked[position(arg)?] = true;
+        }
+    }
+
+    if !picked.contains(&true) {
+        picked.fill(true);
+    }
+    Ok(registry
+        .iter()
+        .zip(picked)
+        .filter(|(_, p)| *p)
+        .map(|(lesson, _)| *lesson)
+        .collect())
+}
+
+fn position(id: &str) -> Result<usize, CliError> {
+    lesson::position(id).ok_or_else(|| CliError::UnknownChapter(id.to_string()))
+}
+
+/**
+ * rust-learn 程序的入口，args 不包含程序名本身
+ * 解析命令行参数并执行命令，命令行错误返回 2，命令失败返回 1
+ */
+pub fn main(args: &[String]) -> ExitCode {
+    let command = match parse(args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}", tr!("cli.error", e));
+            if e.needs_usage() {
+                eprintln!("{}", usage());
+            }
+            return ExitCode::from(2);
+        }
+    };
+
+    let ok = match command {
+        Command::List { functions } => {
+            lesson::print_list(functions);
+            true
+        }
+        Command::Run(options) => match runner::run_all(options) {
+            Ok(failed) => failed == 0,
+            Err(e) => return error(e),
+        },
+        Command::Snapshot(lessons) => {
+            match snapshot::record_all(&snapshot::snapshot_dir(), &lessons) {
+                Ok(()) => true,
+                Err(e) => return error(e),
+            }
+        }
+        Command::Verify(lessons) => snapshot::verify_all(&snapshot::snapshot_dir(), &lessons) == 0,
+        Command::CompileFail(lessons) => match compile_fail::check_all(&lessons) {
+            Ok(failed) => failed == 0,
+            Err(e) => return error(e),
+        },
+        Command::Exercise(command) => return run_exercise(command),
+        Command::Quiz(QuizCommand::Ask(lessons, mut input)) => {
+            match quiz::ask_all(&lessons, &mut input) {
+                Ok(()) => true,
+                Err(e) => return error(e),
+            }
+        }
+        Command::Quiz(QuizCommand::Check(lessons)) => quiz::check_all(&lessons),
+        Command::Progress => {
+            progress::print();
+            true
+        }
+        Command::Shell => {
+            shell::run();
+            true
+        }
+        Command::Watch(lessons) => match watch::run(&lessons) {
+            Ok(()) => true,
+            Err(e) => return error(e),
+        },
+        Command::Explain(codes) => explain::run(&codes),
+        Command::Search(query) => {
+            search::print(&query, &search::search(&query));
+            true
+        }
+        Command::Serve(port) => match serve::run(port) {
+            Ok(()) => true,
+            Err(e) => return error(tr!("serve.cannot_listen", port, e)),
+        },
+        Command::Book(dir) => match book::export(&dir) {
+            Ok(pages) => {
+                println!("{}", tr!("book.written", pages, dir.display()));
+                true
+            }
+            Err(e) => return error(tr!("cli.cannot_write", dir.display(), e)),
+        },
+        Command::Help => {
+            println!("{}", usage());
+            true
+        }
+    };
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+// 打印错误信息，返回失败
+fn error(message: impl Display) -> ExitCode {
+    eprintln!("{}", tr!("cli.error", message));
+    ExitCode::FAILURE
+}
+
+// 练习 id 不存在时和其他命令行错误一样返回 2
+fn run_exercise(command: ExerciseCommand) -> ExitCode {
+    let exercises = match exercise::exercises() {
+        Ok(exercises) => exercises,
+        Err(e) => {
+            return error(tr!(
+                "cli.cannot_read",
+                exercise::exercise_dir().display(),
+                e
+            ))
+        }
+    };
+    let done = match command {
+        ExerciseCommand::List => {
+            exercise::print_list(&exercises);
+            true
+        }
+        ExerciseCommand::Verify(id) => match exercises.iter().find(|e| e.id == id) {
+            Some(exercise) => exercise::report(exercise),
+            None => {
+                let ids: Vec<&str> = exercises.iter().map(|e| e.id.as_str()).collect();
+                error(tr!("exercise.unknown", id, ids.join(", ")));
+                return ExitCode::from(2);
+            }
+        },
+        ExerciseCommand::Next => exercise::next(&exercises),
+    };
+    if done {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    fn ids(lessons: &[&'static dyn Lesson]) -> Vec<&'static str> {
+        lessons.iter().map(|l| l.id()).collect()
+    }
+
+    #[test]
+    fn globals_are_taken_before_the_command() {
+        let (globals, rest) = parse_globals(&args("-vv --quiet --lang en search -q")).unwrap();
+        assert_eq!(globals.verbosity, 1);
+        assert_eq!(globals.locale, Some(Locale::En));
+        assert_eq!(rest, ["search", "-q"]);
+        assert!(matches!(parse_command(&rest), Ok(Command::Search(term)) if term == "-q"));
+
+        let (globals, rest) = parse_globals(&args("quiz --answer -v --answer -qq")).unwrap();
+        assert_eq!(globals.verbosity, 0);
+        assert_eq!(rest, ["quiz", "--answer", "-v", "--answer", "-qq"]);
+        match parse_command(&rest) {
+            Ok(Command::Quiz(QuizCommand::Ask(lessons, Input::Scripted(answers)))) => {
+                assert_eq!(answers, ["-v", "-qq"]);
+                assert_eq!(lessons.len(), lesson::registry().len());
+            }
+            _ => panic!("expected quiz with answers"),
+        }
+    }
+
+    #[test]
+    fn long_globals_are_accepted_anywhere() {
+        let (globals, rest) =
+            parse_globals(&args("run --lang en --log warn,base_04=trace --timestamps")).unwrap();
+        assert_eq!(globals.locale, Some(Locale::En));
+        assert_eq!(globals.filter.unwrap().spec(), "warn,base_04=trace");
+        assert!(globals.timestamps);
+        assert_eq!(rest, ["run"]);
+    }
+
+    #[test]
+    fn bad_globals_are_errors() {
+        assert!(matches!(
+            parse_globals(&args("--lang")),
+            Err(CliError::MissingValue("--lang"))
+        ));
+        assert!(matches!(
+            parse_globals(&args("--lang fr run")),
+            Err(CliError::UnknownLang(lang)) if lang == "fr"
+        ));
+        assert!(matches!(
+            parse_globals(&args("--log loud")),
+            Err(CliError::InvalidLog(spec)) if spec == "loud"
+        ));
+    }
+
+    #[test]
+    fn options_after_the_command_belong_to_it() {
+        assert!(matches!(
+            parse_command(&args("run -v")),
+            Err(CliError::UnknownOption(opt)) if opt == "-v"
+        ));
+        assert!(matches!(
+            parse_command(&args("run --jobs 0")),
+            Err(CliError::InvalidJobs(jobs)) if jobs == "0"
+        ));
+        match parse_command(&args("run --jobs 4 --stats --no-input")) {
+            Ok(Command::Run(options)) => {
+                assert_eq!(options.jobs, 4);
+                assert!(options.stats);
+                assert!(matches!(options.input, Input::Defaults));
+            }
+            _ => panic!("expected run"),
+        }
+        assert!(matches!(parse_command(&[]), Ok(Command::Run(_))));
+        assert!(matches!(
+            parse_command(&args("frobnicate")),
+            Err(CliError::UnknownCommand(cmd)) if cmd == "frobnicate"
+        ));
+    }
+
+    // 章节写错时只打印一行错误，命令或者选项写错时才打印帮助信息
+    #[test]
+    fn only_usage_errors_print_the_usage() {
+        let e = parse_command(&args("run base_99")).err().unwrap();
+        assert!(matches!(&e, CliError::UnknownChapter(id) if id == "base_99"));
+        assert!(!e.needs_usage());
+        assert!(!e.to_string().contains('\n'));
+
+        assert!(CliError::UnknownCommand(String::from("x")).needs_usage());
+        assert!(CliError::UnknownOption(String::from("-x")).needs_usage());
+        assert!(!CliError::UnknownTopic(String::from("x")).needs_usage());
+    }
+
+    #[test]
+    fn select_without_chapters_picks_all() {
+        assert_eq!(ids(&select(&[]).unwrap()), ids(lesson::registry()));
+        assert!(matches!(
+            select(&args("--topic nothing")),
+            Err(CliError::UnknownTopic(topic)) if topic == "nothing"
+        ));
+        assert!(matches!(
+            select(&args("base_99")),
+            Err(CliError::UnknownChapter(id)) if id == "base_99"
+        ));
+    }
+
+    #[cfg(all(
+        feature = "base_02",
+        feature = "base_03",
+        feature = "base_04",
+        feature = "base_05"
+    ))]
+    #[test]
+    fn select_ranges_in_course_order() {
+        assert_eq!(
+            ids(&select(&args("base_02..base_05")).unwrap()),
+            ["base_02", "base_03", "base_04", "base_05"]
+        );
+        // 结果按课程顺序排列且不重复
+        assert_eq!(
+            ids(&select(&args("base_05 base_02..base_03 base_05")).unwrap()),
+            ["base_02", "base_03", "base_05"]
+        );
+        assert_eq!(
+            ids(&select(&args("base_03..base_03")).unwrap()),
+            ["base_03"]
+        );
+        assert!(matches!(
+            select(&args("base_05..base_02")),
+            Err(CliError::EmptyRange(from, to)) if from == "base_05" && to == "base_02"
+        ));
+        assert!(matches!(
+            select(&args("base_02..base_99")),
+            Err(CliError::UnknownChapter(id)) if id == "base_99"
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+// 编译失败的反例 Compile-fail counterexamples
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    lesson::{self, Lesson},
+    rustc,
+};
+
+/**
+ * 反例保存在仓库的 compile_fail 目录下，每个反例一个文件，如 compile_fail/base_01_variables.rs
+ * 文件开头用注释说明反例对应的课程函数和预期的错误码:
+ *
+ * // lesson: base_01::variables
+ * // expect: E0384
+ */
+pub fn case_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("compile_fail")
+}
+
+pub struct Case {
+    pub path: PathBuf,
+    pub name: String,
+    // 对应的课程函数，如 base_01::variables
+    pub lesson: String,
+    // 预期的 rustc 错误码，如 E0384
+    pub expect: String,
+}
+
+impl Case {
+    // 反例所属的章节 id，如 base_01
+    pub fn chapter(&self) -> &str {
+        self.lesson.split("::").next().unwrap_or_default()
+    }
+}
+
+// 读取全部反例，按文件名排序，缺少 lesson 或 expect 注释的文件视为错误
+pub fn cases() -> io::Result<Vec<Case>> {
+    rustc::sources(&case_dir())?
+        .into_iter()
+        .map(parse_case)
+        .collect()
+}
+
+fn parse_case(path: PathBuf) -> io::Result<Case> {
+    let source = fs::read_to_string(&path)?;
+    let lesson = rustc::header(&source, "lesson:");
+    let expect = rustc::header(&source, "expect:");
+    let (Some(lesson), Some(expect)) = (lesson, expect) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: missing `// lesson:` or `// expect:`", path.display()),
+        ));
+    };
+    let name = rustc::stem(&path);
+    Ok(Case {
+        path,
+        name,
+        lesson,
+        expect,
+    })
+}
+
+/**
+ * 检查结果
+ *
+ * Failed:        按预期编译失败，报出了预期的错误码
+ * Compiled:      编译通过了，反例已经失效
+ * OtherErrors:   编译失败，但没有预期的错误码，记录实际的错误码
+ * UnknownLesson: 对应的课程函数已经不存在
+ */
+pub enum Check {
+    Failed,
+    Compiled,
+    OtherErrors(Vec<String>),
+    UnknownLesson,
+}
+
+/**
+ * 用本地的 rustc 编译反例(只做检查，不生成可执行文件)，确认预期的错误仍然出现
+ */
+pub fn check(case: &Case) -> io::Result<Check> {
+    if !matches!(lesson::lookup(&case.lesson), Some((_, Some(_)))) {
+        return Ok(Check::UnknownLesson);
+    }
+
+    let output = rustc::command()
+        .args(["--emit=metadata", "--crate-name"])
+        .arg(&case.name)
+        .arg("--out-dir")
+        .arg(rustc::out_dir("rust-learn-compile-fail"))
+        .arg(&case.path)
+        .output()?;
+
+    if output.status.success() {
+        return Ok(Check::Compiled);
+    }
+    let codes = rustc::error_codes(&String::from_utf8_lossy(&output.stderr));
+    if codes.contains(&case.expect) {
+        Ok(Check::Failed)
+    } else {
+        Ok(Check::OtherErrors(codes))
+    }
+}
+
+/**
+ * 检查选中章节的所有反例并打印结果，返回没有按预期编译失败的反例数
+ * 无法读取 compile_fail 目录时返回错误
+ */
+pub fn check_all(lessons: &[&'static dyn Lesson]) -> io::Result<usize> {
+    let cases = cases().map_err(|e| {
+        let message = tr!("cli.cannot_read", case_dir().display(), e);
+        io::Error::new(e.kind(), message)
+    })?;
+
+    let mut failed = 0;
+    for case in cases {
+        if !lessons.iter().any(|l| l.id() == case.chapter()) {
+            continue;
+        }
+        match check(&case) {
+            Ok(Check::Failed) => {
+                println!("{}", tr!("compile_fail.ok", case.name, case.expect));
+            }
+            Ok(Check::Compiled) => {
+                failed += 1;
+                println!("{}", tr!("compile_fail.compiled", case.name, case.expect));
+            }
+            Ok(Check::OtherErrors(codes)) => {
+                failed += 1;
+                let codes = codes.join(", ");
+                println!(
+                    "{}",
+                    tr!("compile_fail.other", case.name, case.expect, codes)
+                );
+            }
+            Ok(Check::UnknownLesson) => {
+                failed += 1;
+                println!("{}", tr!("compile_fail.unknown", case.name, case.lesson));
+            }
+            Err(e) => {
+                failed += 1;
+                println!("{}", tr!("compile_fail.error", case.name, e));
+            }
+        }
+    }
+    if failed > 0 {
+        eprintln!("{}", tr!("compile_fail.total", failed));
+    }
+    Ok(failed)
+}
@@ -0,0 +1,185 @@
+// 练习 Exercises
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{lesson, progress, rustc};
+
+/**
+ * 练习保存在仓库的 exercises 目录下，每个练习一个文件，如 exercises/base_03_borrow.rs
+ * 文件名(不含 .rs)就是练习的 id，按 id 排序即为练习的顺序
+ * 文件开头用注释说明练习所属的章节和提示:
+ *
+ * // chapter: base_03
+ * // hint: 传入 String 会转移所有权，改成借用 &String 或 &str
+ *
+ * 练习中用 TODO 标出需要修改的地方，修改到能通过编译并且 main 中的断言全部成立即为完成
+ */
+pub fn exercise_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("exercises")
+}
+
+pub struct Exercise {
+    pub path: PathBuf,
+    pub id: String,
+    pub chapter: String,
+    pub hint: String,
+}
+
+/**
+ * 读取编译进来的章节的练习，按 id 排序，缺少 chapter 注释的文件视为错误
+ * 没有启用 feature 的章节的练习不在其中，练习列表、next 和进度统计都以此为准
+ */
+pub fn exercises() -> io::Result<Vec<Exercise>> {
+    let mut exercises = rustc::sources(&exercise_dir())?
+        .into_iter()
+        .map(parse_exercise)
+        .collect::<io::Result<Vec<Exercise>>>()?;
+    exercises.retain(|e| lesson::position(&e.chapter).is_some());
+    Ok(exercises)
+}
+
+fn parse_exercise(path: PathBuf) -> io::Result<Exercise> {
+    let source = fs::read_to_string(&path)?;
+    let Some(chapter) = rustc::header(&source, "chapter:") else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: missing `// chapter:`", path.display()),
+        ));
+    };
+    let hint = rustc::header(&source, "hint:").unwrap_or_default();
+    Ok(Exercise {
+        id: rustc::stem(&path),
+        path,
+        chapter,
+        hint,
+    })
+}
+
+/**
+ * 练习的状态
+ *
+ * Done:         编译通过，运行时断言全部成立
+ * CompileError: 编译失败，记录 rustc 的输出
+ * Failed:       编译通过但运行失败(断言不成立、todo! 等)，记录运行的输出
+ */
+pub enum Status {
+    Done,
+    CompileError(String),
+    Failed(String),
+}
+
+impl Status {
+    pub fn is_done(&self) -> bool {
+        matches!(self, Status::Done)
+    }
+}
+
+// 用本地的 rustc 编译练习并运行，警告不影响结果
+pub fn verify(exercise: &Exercise) -> io::Result<Status> {
+    let out_dir = rustc::out_dir("rust-learn-exercises");
+    fs::create_dir_all(&out_dir)?;
+    let binary = out_dir.join(&exercise.id);
+
+    let output = rustc::command()
+        .args(["-A", "warnings", "-o"])
+        .arg(&binary)
+        .arg(&exercise.path)
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        return Ok(Status::CompileError(stderr));
+    }
+
+    // 练习中的断言失败时只需要看到 panic 信息，不需要调用栈
+    let output = Command::new(&binary).env("RUST_BACKTRACE", "0").output()?;
+    if output.status.success() {
+        Ok(Status::Done)
+    } else {
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok(Status::Failed(text))
+    }
+}
+
+// 检查所有练习并列出是否完成，最后打印完成数
+pub fn print_list(exercises: &[Exercise]) {
+    let mut done = 0;
+    for exercise in exercises {
+        match verify(exercise) {
+            Ok(status) if status.is_done() => {
+                done += 1;
+                println!("{}", tr!("exercise.done", exercise.id, exercise.chapter));
+            }
+            Ok(_) => {
+                println!("{}", tr!("exercise.pending", exercise.id, exercise.chapter));
+            }
+            Err(e) => println!("{}", tr!("exercise.error", exercise.id, e)),
+        }
+    }
+    println!("{}", tr!("exercise.progress", done, exercises.len()));
+}
+
+/**
+ * 按顺序找到第一个没有完成的练习并检查它，返回是否全部完成
+ */
+pub fn next(exercises: &[Exercise]) -> bool {
+    for exercise in exercises {
+        match verify(exercise) {
+            Ok(Status::Done) => continue,
+            Ok(_) => {
+                let path = exercise.path.display();
+                println!("{}", tr!("exercise.next", exercise.id, path));
+                report(exercise);
+                return false;
+            }
+            Err(e) => {
+                println!("{}", tr!("exercise.error", exercise.id, e));
+                return false;
+            }
+        }
+    }
+    println!("{}", tr!("exercise.all_done", exercises.len()));
+    true
+}
+
+/**
+ * 检查一个练习并记录进度，打印结果，返回是否完成
+ * 没有完成时打印编译或运行的输出以及提示
+ */
+pub fn report(exercise: &Exercise) -> bool {
+    let status = verify(exercise);
+    if let Ok(status) = &status {
+        progress::update(|p| p.record_exercise(&exercise.id, status.is_done()));
+    }
+    let output = match status {
+        Ok(Status::Done) => {
+            println!("{}", tr!("exercise.passed", exercise.id));
+            return true;
+        }
+        Ok(Status::CompileError(output)) => {
+            println!("{}", tr!("exercise.compile_error", exercise.id));
+            output
+        }
+        Ok(Status::Failed(output)) => {
+            println!("{}", tr!("exercise.failed", exercise.id));
+            output
+        }
+        Err(e) => {
+            println!("{}", tr!("exercise.error", exercise.id, e));
+            return false;
+        }
+    };
+    println!("{}", output.trim_end());
+    if !exercise.hint.is_empty() {
+        println!("{}", tr!("exercise.hint", exercise.hint));
+    }
+    println!(
+        "{}",
+        tr!("exercise.edit", exercise.path.display(), exercise.id)
+    );
+    false
+}
@@ -0,0 +1,288 @@
+// 错误码解释 Offline rustc error code explainer
+
+use std::io::{self, BufRead, IsTerminal};
+
+use crate::{compile_fail, i18n, lesson, rustc, source};
+
+/**
+ * 内置解释的错误码，以及讲解它的课程函数
+ *
+ * 解释的文字在消息目录中，如 explain.e0499 是一句话的概括，explain.e0499_detail 是详细说明
+ * lessons 是章节中的函数(可以是课程函数，也可以是章节中的其他函数)，第一个是最相关的
+ */
+pub struct Entry {
+    pub code: &'static str,
+    pub lessons: &'static [&'static str],
+}
+
+const ENTRIES: &[Entry] = &[
+    Entry {
+        code: "E0106",
+        lessons: &["base_10::longest", "base_10::life_cycle_example"],
+    },
+    Entry {
+        code: "E0308",
+        lessons: &["base_02::add_with_extra", "base_02::statement_expression"],
+    },
+    Entry {
+        code: "E0382",
+        lessons: &[
+            "base_03::pass_return",
+            "base_03::example",
+            "base_04::struct_example",
+            "base_09::hash_map_example",
+        ],
+    },
+    Entry {
+        code: "E0384",
+        lessons: &["base_01::variables"],
+    },
+    Entry {
+        code: "E0499",
+        lessons: &["base_03::repeat_mutable_borrow"],
+    },
+    Entry {
+        code: "E0502",
+        lessons: &["base_03::repeat_mutable_borrow", "base_09::vector_example"],
+    },
+    Entry {
+        code: "E0506",
+        lessons: &["base_04::string_slice"],
+    },
+];
+
+impl Entry {
+    // 一句话的概括
+    pub fn summary(&self) -> String {
+        i18n::t(&format!("explain.{}", self.code.to_lowercase())).to_string()
+    }
+
+    pub fn detail(&self) -> String {
+        i18n::t(&format!("explain.{}_detail", self.code.to_lowercase())).to_string()
+    }
+
+    /**
+     * 讲解这个错误的函数和它在源文件中的位置，如 (base_03::pass_return, src/base_03.rs:25)
+     * 没有编译进来的章节(见 Cargo.toml 中的 feature)不出现在结果中
+     */
+    pub fn lessons(&self) -> Vec<(&'static str, String)> {
+        self.lessons
+            .iter()
+            .filter_map(|path| {
+                let (id, name) = path.split_once("::")?;
+                let snippet = source::find(id, name)?;
+                Some((*path, format!("src/{}.rs:{}", id, snippet.line)))
+            })
+            .collect()
+    }
+}
+
+// 全部内置解释的错误码
+pub fn codes() -> Vec<&'static str> {
+    ENTRIES.iter().map(|entry| entry.code).collect()
+}
+
+/**
+ * 查找错误码的解释，不区分大小写，可以省略 E 和前面的 0，如 e0499、0499、499
+ */
+pub fn find(code: &str) -> Option<&'static Entry> {
+    let digits = code
+        .strip_prefix(['E', 'e'])
+        .unwrap_or(code)
+        .parse::<u32>()
+        .ok()?;
+    let code = format!("E{:04}", digits);
+    ENTRIES.iter().find(|entry| entry.code == code)
+}
+
+/**
+ * 打印错误码的详细解释、相关的课程函数，以及 compile_fail 目录下报出这个错误的反例
+ *
+ * E0499 同一时间只能有一个可变引用
+ * ...
+ */
+pub fn print(entry: &Entry) {
+    println!("{} {}", entry.code, entry.summary());
+    println!();
+    for line in entry.detail().lines() {
+        println!("{}", line);
+    }
+
+    let lessons = entry.lessons();
+    if !lessons.is_empty() {
+        println!();
+        println!("{}", tr!("explain.lessons"));
+        let width = lessons
+            .iter()
+            .map(|(path, _)| path.len())
+            .max()
+            .unwrap_or(0);
+        for (path, location) in &lessons {
+            println!("    {:<width$}  {}", path, location);
+        }
+    }
+
+    let cases: Vec<compile_fail::Case> = compile_fail::cases()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|case| case.expect == entry.code)
+        .filter(|case| lesson::position(case.chapter()).is_some())
+        .collect();
+    if !cases.is_empty() {
+        println!();
+        println!("{}", tr!("explain.cases"));
+        for case in cases {
+            println!("    compile_fail/{}.rs", case.name);
+        }
+    }
+}
+
+/**
+ * explain 命令: 依次解释给出的错误码，没有给出错误码时从标准输入读取 rustc 的输出并加上注解
+ * 返回是否成功，有错误码没有内置解释或者读取标准输入出错时为 false
+ */
+pub fn run(codes: &[String]) -> bool {
+    if codes.is_empty() {
+        if io::stdin().is_terminal() {
+            eprintln!("{}", tr!("explain.paste"));
+        }
+        return match annotate(io::stdin().lock()) {
+            Ok(0) => {
+                eprintln!("{}", tr!("explain.no_errors"));
+                true
+            }
+            Ok(errors) => {
+                eprintln!("{}", tr!("explain.found", errors));
+                true
+            }
+            Err(e) => {
+                eprintln!("{}", tr!("cli.error", e));
+                false
+            }
+        };
+    }
+
+    let mut known = true;
+    for (i, code) in codes.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        match find(code) {
+            Some(entry) => print(entry),
+            None => {
+                known = false;
+                let message = tr!("explain.unknown", code, self::codes().join(", "));
+                eprintln!("{}", tr!("cli.error", message));
+            }
+        }
+    }
+    known
+}
+
+/**
+ * 逐行原样输出 rustc 的输出，每个 error[Exxxx] 之后加一行注解，返回找到的错误数
+ *
+ * error[E0499]: cannot borrow `s` as mutable more than once at a time
+ *   = rust-learn: E0499 同一时间只能有一个可变引用，见 base_03::repeat_mutable_borrow (src/base_03.rs:77)
+ *
+ * 从终端复制的带颜色的输出也可以识别
+ */
+pub fn annotate(reader: impl BufRead) -> io::Result<usize> {
+    let mut errors = 0;
+    for line in reader.lines() {
+        let line = line?;
+        println!("{}", line);
+
+        let plain = strip_ansi(&line);
+        let Some(code) = rustc::error_code(&plain) else {
+            continue;
+        };
+        errors += 1;
+        let note = match find(code) {
+            Some(entry) => match entry.lessons().first() {
+                Some((path, location)) => {
+                    tr!("explain.note", entry.code, entry.summary(), path, location)
+                }
+                None => format!("{} {}", entry.code, entry.summary()),
+            },
+            None => tr!("explain.note_unknown", code, code),
+        };
+        println!("  = rust-learn: {}", note);
+    }
+    Ok(errors)
+}
+
+// 去掉终端颜色的转义序列，如 \x1b[1m\x1b[38;5;9m
+fn strip_ansi(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // 转义序列以 [ 开头，以字母结束
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_accepts_short_forms() {
+        for code in ["E0499", "e0499", "0499", "499"] {
+            assert_eq!(find(code).map(|e| e.code), Some("E0499"), "{}", code);
+        }
+        assert_eq!(find("E502").map(|e| e.code), Some("E0502"));
+    }
+
+    #[test]
+    fn find_rejects_unknown_codes() {
+        assert!(find("E0001").is_none());
+        assert!(find("E").is_none());
+        assert!(find("borrow").is_none());
+        assert!(find("").is_none());
+    }
+
+    #[test]
+    fn entries_are_sorted_and_explained() {
+        let codes = codes();
+        assert!(codes.windows(2).all(|w| w[0] < w[1]));
+        for entry in ENTRIES {
+            assert!(!entry.lessons.is_empty(), "{}", entry.code);
+            let key = format!("explain.{}_detail", entry.code.to_lowercase());
+            assert!(i18n::message(i18n::Locale::Zh, &key).is_some(), "{}", key);
+            assert!(i18n::message(i18n::Locale::En, &key).is_some(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn strip_ansi_removes_escape_sequences() {
+        assert_eq!(
+            strip_ansi("\x1b[1m\x1b[38;5;9merror[E0502]\x1b[0m\x1b[1m: cannot borrow\x1b[0m"),
+            "error[E0502]: cannot borrow"
+        );
+        assert_eq!(strip_ansi("plain 中文"), "plain 中文");
+        // 没有结束字母的转义序列吞掉到行尾
+        assert_eq!(strip_ansi("text\x1b[38;5"), "text");
+    }
+
+    #[test]
+    fn annotate_counts_errors() {
+        let output = "warning: unused variable: `x`\n\
+                      error[E0502]: cannot borrow `v` as mutable because it is also borrowed as immutable\n\
+                      \x20 --> src/main.rs:4:5\n\
+                      \x1b[1m\x1b[38;5;9merror[E0499]\x1b[0m: cannot borrow `s` as mutable more than once\n\
+                      error[E0277]: the trait bound is not satisfied\n\
+                      error: aborting due to 3 previous errors\n";
+        assert_eq!(annotate(output.as_bytes()).unwrap(), 3);
+        assert_eq!(annotate("warning: unused\n".as_bytes()).unwrap(), 0);
+    }
+}
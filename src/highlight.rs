@@ -0,0 +1,189 @@
+// 语法高亮 Syntax highlighting for the terminal and HTML
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use crate::{
+    lexer::{self, Kind, Token},
+    report::xml_escape,
+};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32",
+    "f64", "bool", "char", "str",
+];
+
+/**
+ * 高亮的种类
+ * 类型包括内置类型和大写开头的名字，字符串包括字符字面量
+ */
+#[derive(Clone, Copy)]
+enum Style {
+    Keyword,
+    Type,
+    Macro,
+    String,
+    Number,
+    Lifetime,
+    Comment,
+}
+
+impl Style {
+    // ANSI 颜色
+    fn ansi(self) -> &'static str {
+        match self {
+            Style::Keyword => "35",
+            Style::Type => "33",
+            Style::Macro => "34",
+            Style::String => "32",
+            Style::Number => "36",
+            Style::Lifetime => "95",
+            Style::Comment => "90",
+        }
+    }
+
+    // HTML 中 span 的 class，颜色由 HTML 导出的样式表决定
+    fn class(self) -> &'static str {
+        match self {
+            Style::Keyword => "kw",
+            Style::Type => "ty",
+            Style::Macro => "mac",
+            Style::String => "str",
+            Style::Number => "num",
+            Style::Lifetime => "lt",
+            Style::Comment => "com",
+        }
+    }
+}
+
+/**
+ * 标准输出是终端时才使用颜色
+ * 设置了 NO_COLOR(见 https://no-color.org)或者 TERM=dumb 时不使用颜色
+ */
+pub fn enabled() -> bool {
+    io::stdout().is_terminal()
+        && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        && !env::var("TERM").is_ok_and(|term| term == "dumb")
+}
+
+/**
+ * 用 ANSI 颜色高亮一段 Rust 代码，去掉颜色之后与原文完全相同
+ * 多行的 token(如块注释、多行字符串)逐行着色，避免颜色跨行影响其他输出
+ */
+pub fn highlight(code: &str) -> String {
+    let mut result = String::with_capacity(code.len() * 2);
+    for (style, text) in spans(code) {
+        let Some(style) = style else {
+            result.push_str(text);
+            continue;
+        };
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                result.push('\n');
+            }
+            if !line.is_empty() {
+                result.push_str(&format!("\x1b[{}m{}\x1b[0m", style.ansi(), line));
+            }
+        }
+    }
+    result
+}
+
+// 高亮成 HTML，放在 <pre><code> 中使用
+pub fn highlight_html(code: &str) -> String {
+    let mut result = String::with_capacity(code.len() * 2);
+    for (style, text) in spans(code) {
+        match style {
+            Some(style) => result.push_str(&format!(
+                "<span class=\"{}\">{}</span>",
+                style.class(),
+                xml_escape(text)
+            )),
+            None => result.push_str(&xml_escape(text)),
+        }
+    }
+    result
+}
+
+/**
+ * 把代码切分成带高亮种类的片段，所有片段首尾相接就是原文
+ * 数字的类型后缀(如 1.0f32 中的 f32)单独作为类型
+ */
+fn spans(code: &str) -> Vec<(Option<Style>, &str)> {
+    let tokens = lexer::tokenize(code);
+    let mut spans = Vec::with_capacity(tokens.len());
+
+    for (i, token) in tokens.iter().enumerate() {
+        let style = match token.kind {
+            Kind::Number => {
+                let (digits, suffix) = split_suffix(token.text);
+                spans.push((Some(Style::Number), digits));
+                if !suffix.is_empty() {
+                    spans.push((Some(Style::Type), suffix));
+                }
+                continue;
+            }
+            Kind::LineComment | Kind::BlockComment => Some(Style::Comment),
+            Kind::Str | Kind::Char => Some(Style::String),
+            Kind::Lifetime => Some(Style::Lifetime),
+            Kind::Ident => ident_style(token, tokens.get(i + 1)),
+            Kind::Whitespace | Kind::Punct => None,
+        };
+        spans.push((style, token.text));
+    }
+    spans
+}
+
+// 紧跟着 ! 的名字是宏，如 println!
+fn ident_style(token: &Token, next: Option<&Token>) -> Option<Style> {
+    let text = token.text;
+    if KEYWORDS.contains(&text) {
+        Some(Style::Keyword)
+    } else if next.is_some_and(|next| next.text == "!") {
+        Some(Style::Macro)
+    } else if PRIMITIVES.contains(&text) || text.starts_with(char::is_uppercase) {
+        Some(Style::Type)
+    } else {
+        None
+    }
+}
+
+/**
+ * 把数字字面量拆成数字部分和类型后缀，如 1.0f32 拆成 1.0 和 f32，100_000 没有后缀
+ * 十六进制中 f 是数字，所以 0x1f32 没有后缀
+ */
+fn split_suffix(number: &str) -> (&str, &str) {
+    let hex = number.starts_with("0x") || number.starts_with("0X");
+    PRIMITIVES
+        .iter()
+        .filter(|suffix| suffix.starts_with(['i', 'u']) || (!hex && suffix.starts_with('f')))
+        .find(|suffix| number.len() > suffix.len() && number.ends_with(*suffix))
+        .map_or((number, ""), |suffix| {
+            number.split_at(number.len() - suffix.len())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_number_suffix() {
+        assert_eq!(split_suffix("1.0f32"), ("1.0", "f32"));
+        assert_eq!(split_suffix("10usize"), ("10", "usize"));
+        assert_eq!(split_suffix("5u8"), ("5", "u8"));
+        assert_eq!(split_suffix("100_000"), ("100_000", ""));
+        // 十六进制中的 f 是数字
+        assert_eq!(split_suffix("0x1f32"), ("0x1f32", ""));
+        assert_eq!(split_suffix("0xffu8"), ("0xff", "u8"));
+    }
+}
@@ -0,0 +1,308 @@
+// 多语言消息 Message catalog (zh / en)
+
+use crate::stats;
+
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    env,
+    fmt::{Display, Write},
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+};
+
+/**
+ * 消息目录放在 locales 目录下，编译时嵌入到程序中
+ * 每行一条 key = value，# 开头的行是注释，value 中的 \n 表示换行
+ * value 中用 {} 按顺序引用参数，也可以用 {0} {1} 指定位置，{{ }} 表示花括号本身
+ */
+const ZH: &str = include_str!("../locales/zh.txt");
+const EN: &str = include_str!("../locales/en.txt");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Zh,
+    En,
+}
+
+impl Locale {
+    // 解析 zh / en，也接受 zh_CN.UTF-8、en_US 这种 LANG 的写法
+    pub fn parse(s: &str) -> Option<Locale> {
+        let lang = s.split(['_', '-', '.']).next().unwrap_or_default();
+        match lang.to_ascii_lowercase().as_str() {
+            "zh" => Some(Locale::Zh),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    /**
+     * 按 LC_ALL、LC_MESSAGES、LANG 的顺序从环境变量中选择语言
+     * 跳过没有设置或者无法识别的变量，如 LC_ALL=C.UTF-8 时继续看 LANG
+     * 都没有设置或者无法识别时使用中文，课程原本就是中文的
+     */
+    pub fn from_env() -> Locale {
+        Locale::from_vars(|name| env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Locale {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| var(name))
+            .filter_map(|value| Locale::parse(&value))
+            .next()
+            .unwrap_or(Locale::Zh)
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Locale::Zh => ZH,
+            Locale::En => EN,
+        }
+    }
+}
+
+// 当前语言，0 表示还没有设置，第一次使用时从环境变量中选择
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+thread_local! {
+    // with_locale 在当前线程上临时指定的语言，优先于 CURRENT
+    static SCOPED: Cell<Option<Locale>> = const { Cell::new(None) };
+}
+
+pub fn locale() -> Locale {
+    if let Some(locale) = SCOPED.with(Cell::get) {
+        return locale;
+    }
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Locale::Zh,
+        2 => Locale::En,
+        _ => {
+            let locale = Locale::from_env();
+            set_locale(locale);
+            locale
+        }
+    }
+}
+
+pub fn set_locale(locale: Locale) {
+    let value = match locale {
+        Locale::Zh => 1,
+        Locale::En => 2,
+    };
+    CURRENT.store(value, Ordering::Relaxed);
+}
+
+/**
+ * 在当前线程上以 locale 运行 f，结束之后(包括 f panic 时)恢复原来的语言
+ * 只影响当前线程，不改变 set_locale 设置的语言，快照和测验检查用它总是以中文运行课程
+ */
+pub fn with_locale<R>(locale: Locale, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Locale>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED.with(|scoped| scoped.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SCOPED.with(|scoped| scoped.replace(Some(locale))));
+    f()
+}
+
+fn catalog(locale: Locale) -> &'static HashMap<&'static str, String> {
+    static ZH_CATALOG: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
+    static EN_CATALOG: OnceLock<HashMap<&'static str, String>> = OnceLock::new();
+    let cell = match locale {
+        Locale::Zh => &ZH_CATALOG,
+        Locale::En => &EN_CATALOG,
+    };
+    cell.get_or_init(|| parse_catalog(locale.source()))
+}
+
+fn parse_catalog(source: &'static str) -> HashMap<&'static str, String> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim().replace("\\n", "\n")))
+        .collect()
+}
+
+/**
+ * 查找当前语言的消息
+ * 当前语言缺少这条消息时使用中文，中文也没有时直接返回 key 本身
+ */
+pub fn t(key: &str) -> &str {
+    stats::untracked(|| {
+        catalog(locale())
+            .get(key)
+            .or_else(|| catalog(Locale::Zh).get(key))
+            .map(String::as_str)
+            .unwrap_or(key)
+    })
+}
+
+// 指定语言中的消息，没有这条消息时返回 None，不做任何回退
+pub fn message(locale: Locale, key: &str) -> Option<&'static str> {
+    catalog(locale).get(key).map(String::as_str)
+}
+
+/**
+ * 把参数填入消息模板，{} 按顺序取参数，{0} {1} 按位置取参数
+ * 课程原来直接 println! 字面量，这里的分配不计入课程的统计，见 stats::untracked
+ */
+pub fn format(template: &str, args: &[&dyn Display]) -> String {
+    stats::untracked(|| fill(template, args))
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            result.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        let placeholder = rest
+            .strip_prefix('{')
+            .and_then(|r| r.find('}').map(|end| &r[..end]));
+        let index = match placeholder {
+            Some("") => {
+                next += 1;
+                Some(next - 1)
+            }
+            Some(n) => n.parse::<usize>().ok(),
+            None => None,
+        };
+        match (placeholder, index.and_then(|i| args.get(i))) {
+            (Some(p), Some(arg)) => {
+                let _ = write!(result, "{}", arg);
+                rest = &rest[p.len() + 2..];
+            }
+            // 不认识的占位符或者参数不够，原样保留
+            _ => {
+                result.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/**
+ * 取当前语言的消息并填入参数，返回 String
+ *
+ * tr!("base_01.value_of_x", x)
+ */
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::format($crate::i18n::t($key), &[])
+    };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format(
+            $crate::i18n::t($key),
+            &[$(&$arg as &dyn ::std::fmt::Display),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 用固定的变量代替真实环境变量，测试之间并行运行也不会互相影响
+    fn from_vars(vars: &[(&str, &str)]) -> Locale {
+        Locale::from_vars(|name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn parse_accepts_lang_forms() {
+        assert_eq!(Locale::parse("zh"), Some(Locale::Zh));
+        assert_eq!(Locale::parse("zh_CN.UTF-8"), Some(Locale::Zh));
+        assert_eq!(Locale::parse("EN-us"), Some(Locale::En));
+        assert_eq!(Locale::parse("C.UTF-8"), None);
+        assert_eq!(Locale::parse(""), None);
+    }
+
+    #[test]
+    fn from_env_skips_unknown_locales() {
+        let vars = [("LC_ALL", "C.UTF-8"), ("LANG", "en_US.UTF-8")];
+        assert_eq!(from_vars(&vars), Locale::En);
+        let vars = [("LC_ALL", ""), ("LC_MESSAGES", "POSIX"), ("LANG", "zh_CN")];
+        assert_eq!(from_vars(&vars), Locale::Zh);
+        let vars = [("LC_MESSAGES", "en"), ("LANG", "zh_CN")];
+        assert_eq!(from_vars(&vars), Locale::En);
+        assert_eq!(from_vars(&[("LANG", "C")]), Locale::Zh);
+        assert_eq!(from_vars(&[]), Locale::Zh);
+    }
+
+    #[test]
+    fn with_locale_is_scoped_to_the_thread() {
+        let outer = locale();
+        let inner = with_locale(Locale::En, || {
+            let other = std::thread::spawn(locale).join().unwrap();
+            let nested = with_locale(Locale::Zh, locale);
+            (locale(), nested, other)
+        });
+        assert_eq!(inner, (Locale::En, Locale::Zh, outer));
+        assert_eq!(locale(), outer);
+
+        let panicked = std::panic::catch_unwind(|| with_locale(Locale::En, || panic!("boom")));
+        assert!(panicked.is_err());
+        assert_eq!(locale(), outer);
+    }
+
+    #[test]
+    fn format_fills_placeholders_in_order() {
+        assert_eq!(format("{} + {} = {}", &[&1, &2, &3]), "1 + 2 = 3");
+        assert_eq!(format("no args", &[]), "no args");
+    }
+
+    #[test]
+    fn format_fills_positional_placeholders() {
+        assert_eq!(format("{1} {0} {1}", &[&"a", &"b"]), "b a b");
+        // {} 从第一个参数开始计数，和 {0} 这种写法互不影响
+        assert_eq!(format("{0} {} {}", &[&"a", &"b"]), "a a b");
+    }
+
+    #[test]
+    fn format_escapes_braces() {
+        assert_eq!(format("{{}} {}", &[&1]), "{} 1");
+        assert_eq!(format("{{{}}}", &[&"x"]), "{x}");
+    }
+
+    #[test]
+    fn format_keeps_unknown_placeholders() {
+        assert_eq!(format("{} {}", &[&1]), "1 {}");
+        assert_eq!(format("{name} {5}", &[&1]), "{name} {5}");
+        assert_eq!(format("open { and close }", &[]), "open { and close }");
+    }
+
+    #[test]
+    fn catalogs_have_the_same_keys() {
+        let zh = catalog(Locale::Zh);
+        let en = catalog(Locale::En);
+        let mut missing: Vec<&str> = zh
+            .keys()
+            .filter(|k| !en.contains_key(*k))
+            .copied()
+            .collect();
+        missing.extend(en.keys().filter(|k| !zh.contains_key(*k)).copied());
+        assert!(missing.is_empty(), "{:?}", missing);
+    }
+}
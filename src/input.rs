@@ -0,0 +1,53 @@
+// 课程输入 Lesson input
+
+use std::{collections::VecDeque, fs, io, path::Path};
+
+/**
+ * 需要读取输入的课程函数(如 base_04::array_example)从 Input 读取，而不是直接读 stdin
+ *
+ * Stdin:    从标准输入读取，读到 EOF 时(如 CI 中 stdin 为空)使用默认答案
+ * Scripted: 按顺序使用预先给定的答案，用完之后使用默认答案
+ * Defaults: 不读取任何输入，总是使用默认答案，用于无人值守运行
+ */
+pub enum Input {
+    Stdin,
+    Scripted(VecDeque<String>),
+    Defaults,
+}
+
+impl Input {
+    pub fn scripted<I, S>(answers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Input::Scripted(answers.into_iter().map(Into::into).collect())
+    }
+
+    // 从文件读取答案，每行一个
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Ok(Input::scripted(text.lines()))
+    }
+
+    /**
+     * 与 io::stdin().read_line 用法相同，读到的一行追加到 buf 中(包含换行符)
+     * default 是无法获得输入时使用的答案
+     */
+    pub fn read_line(&mut self, buf: &mut String, default: &str) -> io::Result<usize> {
+        let answer = match self {
+            Input::Stdin => {
+                let n = io::stdin().read_line(buf)?;
+                if n > 0 {
+                    return Ok(n);
+                }
+                default.to_string()
+            }
+            Input::Scripted(answers) => answers.pop_front().unwrap_or_else(|| default.to_string()),
+            Input::Defaults => default.to_string(),
+        };
+        buf.push_str(&answer);
+        buf.push('\n');
+        Ok(answer.len() + 1)
+    }
+}
@@ -0,0 +1,171 @@
+// 课程注册表 Lesson registry
+
+use crate::{input::Input, log::Level, output::Output};
+
+/**
+ * 课程函数，如 base_01::variables
+ *
+ * run 统一了课程函数的签名，不需要输入或者需要额外参数的函数用闭包包一层
+ */
+pub struct Section {
+    pub name: &'static str,
+    pub run: fn(&mut Output, &mut Input),
+}
+
+impl Section {
+    pub const fn new(name: &'static str, run: fn(&mut Output, &mut Input)) -> Self {
+        Section { name, run }
+    }
+}
+
+/**
+ * 运行课程函数的回调，由它负责调用 section.run
+ * 调用方可以借此在每个课程函数前后做额外的事情，如计时
+ */
+pub type Each<'a> = dyn FnMut(&Section, &mut Output, &mut Input) + 'a;
+
+/**
+ * 每个 base_XX 模块都实现 Lesson 特征，然后登记到下面的 LESSONS 中
+ *
+ * id:       章节编号，如 base_01
+ * title:    章节标题，中英文
+ * topics:   主题标签，用于按主题筛选章节
+ * sections: 本章的课程函数，按运行顺序排列
+ * run_with: 运行该章节，输出写到 out 中，需要输入时从 input 读取，每个课程函数都通过 each 调用
+ *           默认在 banners 之间按顺序运行 sections
+ *
+ * banners:   章节开始和结束时打印的横幅，是消息目录中的 key，没有横幅的章节返回 None
+ * unordered: 输出顺序不固定的行前缀(如遍历 HashMap 打印的行)，快照比较时会对这些行排序
+ * serial:    是否只能串行运行，需要交互输入或者读写共享文件的章节返回 true，
+ *            run --jobs 并行运行时这些章节不进入线程池，而是在主线程中按顺序运行
+ */
+pub trait Lesson: Sync {
+    fn id(&self) -> &'static str;
+
+    fn title(&self) -> &'static str;
+
+    fn topics(&self) -> &'static [&'static str];
+
+    fn sections(&self) -> &'static [Section];
+
+    fn run_with(&self, out: &mut Output, input: &mut Input, each: &mut Each) {
+        let banners = self.banners();
+        if let Some((start, _)) = banners {
+            banner(out, self.id(), start);
+        }
+        for section in self.sections() {
+            each(section, out, input);
+        }
+        if let Some((_, end)) = banners {
+            banner(out, self.id(), end);
+        }
+    }
+
+    fn banners(&self) -> Option<(&'static str, &'static str)> {
+        None
+    }
+
+    fn unordered(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn serial(&self) -> bool {
+        false
+    }
+}
+
+// 横幅的日志 target 是章节 id，和章节里 out_dbg! 的一样，可以用 --log base_04=warn 单独关掉
+fn banner(out: &mut Output, id: &str, key: &str) {
+    out.log(
+        Level::Info,
+        id,
+        format_args!("========== {} ==========\n", tr!(key)),
+    );
+}
+
+/**
+ * 所有编译进来的章节，按课程顺序排列，没有启用 feature 的章节不在其中
+ */
+static LESSONS: &[&dyn Lesson] = &[
+    #[cfg(feature = "base_01")]
+    &crate::base_01::Chapter,
+    #[cfg(feature = "base_02")]
+    &crate::base_02::Chapter,
+    #[cfg(feature = "base_03")]
+    &crate::base_03::Chapter,
+    #[cfg(feature = "base_04")]
+    &crate::base_04::Chapter,
+    #[cfg(feature = "base_05")]
+    &crate::base_05::Chapter,
+    #[cfg(feature = "base_06")]
+    &crate::base_06::Chapter,
+    #[cfg(feature = "base_07")]
+    &crate::base_07::Chapter,
+    #[cfg(feature = "base_08")]
+    &crate::base_08::Chapter,
+    #[cfg(feature = "base_09")]
+    &crate::base_09::Chapter,
+    #[cfg(feature = "base_10")]
+    &crate::base_10::Chapter,
+    #[cfg(feature = "base_11")]
+    &crate::base_11::Chapter,
+];
+
+pub fn registry() -> &'static [&'static dyn Lesson] {
+    LESSONS
+}
+
+/**
+ * 按课程顺序列出章节的 id、标题和主题，functions 为 true 时同时列出每个章节的课程函数
+ */
+pub fn print_list(functions: bool) {
+    for lesson in registry() {
+        println!(
+            "{}  {}  [{}]",
+            lesson.id(),
+            lesson.title(),
+            lesson.topics().join(", ")
+        );
+        if functions {
+            for section in lesson.sections() {
+                println!("    {}::{}", lesson.id(), section.name);
+            }
+        }
+    }
+}
+
+// 通过 id 查找章节在课程中的位置
+pub fn position(id: &str) -> Option<usize> {
+    registry().iter().position(|lesson| lesson.id() == id)
+}
+
+/**
+ * 通过路径查找章节或课程函数
+ * base_03 返回章节本身，base_03::quote 同时返回其中的课程函数
+ * 章节或课程函数不存在时返回 None
+ */
+pub fn lookup(path: &str) -> Option<(&'static dyn Lesson, Option<&'static Section>)> {
+    let (id, name) = match path.split_once("::") {
+        Some((id, name)) => (id, Some(name)),
+        None => (path, None),
+    };
+    let lesson = registry()[position(id)?];
+    match name {
+        None => Some((lesson, None)),
+        Some(name) => {
+            let section = lesson.sections().iter().find(|s| s.name == name)?;
+            Some((lesson, Some(section)))
+        }
+    }
+}
+
+// 所有章节用到的主题标签，去重后按字母排序
+pub fn topics() -> Vec<&'static str> {
+    let mut topics: Vec<&'static str> = registry()
+        .iter()
+        .flat_map(|lesson| lesson.topics().iter().copied())
+        .collect();
+    topics.sort_unstable();
+    topics.dedup();
+    topics
+}
@@ -0,0 +1,328 @@
+// Rust 词法分析 A small Rust lexer
+
+/**
+ * 词法单元的种类
+ *
+ * 这里只区分展示和查找源代码需要的种类，不做完整的 Rust 词法分析:
+ * 关键字和标识符都是 Ident，运算符和括号都是单个字符的 Punct
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+    Str,
+    Char,
+    Lifetime,
+    Ident,
+    Number,
+    Punct,
+}
+
+/**
+ * 一个词法单元，text 是源代码中的原文(source 的切片)
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct Token<'a> {
+    pub kind: Kind,
+    pub text: &'a str,
+}
+
+/**
+ * 把源代码切分成词法单元，所有单元的 text 首尾相接就是完整的源代码
+ * 遇到不认识的字符时作为单个字符的 Punct，不会失败
+ */
+pub fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+
+    while let Some(c) = rest.chars().next() {
+        let (kind, len) = match prefixed_literal(rest) {
+            Some(literal) => literal,
+            None => match c {
+                c if c.is_whitespace() => (Kind::Whitespace, take_while(rest, char::is_whitespace)),
+                '/' if rest.starts_with("//") => {
+                    (Kind::LineComment, rest.find('\n').unwrap_or(rest.len()))
+                }
+                '/' if rest.starts_with("/*") => (Kind::BlockComment, block_comment(rest)),
+                '"' => (Kind::Str, string(rest, 0)),
+                '\'' => quote(rest),
+                c if c.is_ascii_digit() => (Kind::Number, number(rest)),
+                c if c == '_' || c.is_alphabetic() => (Kind::Ident, ident(rest)),
+                c => (Kind::Punct, c.len_utf8()),
+            },
+        };
+        let (text, tail) = rest.split_at(len);
+        tokens.push(Token { kind, text });
+        rest = tail;
+    }
+    tokens
+}
+
+fn take_while(s: &str, f: impl Fn(char) -> bool) -> usize {
+    s.find(|c| !f(c)).unwrap_or(s.len())
+}
+
+fn ident(s: &str) -> usize {
+    take_while(s, |c| c == '_' || c.is_alphanumeric())
+}
+
+// 块注释可以嵌套，如 /* a /* b */ c */
+fn block_comment(s: &str) -> usize {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < s.len() {
+        if s[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if s[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += s[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    s.len()
+}
+
+/**
+ * 字符串，从 s[start] 的引号开始，到下一个没有被转义的引号结束
+ * 没有结束的引号时一直到源代码末尾
+ */
+fn string(s: &str, start: usize) -> usize {
+    let mut escaped = false;
+    for (i, c) in s[start + 1..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return start + 1 + i + 1,
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+// 原始字符串 r#"..."#，start 指向 r 之后的第一个 # 或引号
+fn raw_string(s: &str, start: usize) -> usize {
+    let hashes = take_while(&s[start..], |c| c == '#');
+    let close = format!("\"{}", "#".repeat(hashes));
+    let body = start + hashes + 1;
+    match s[body..].find(&close) {
+        Some(end) => body + end + close.len(),
+        None => s.len(),
+    }
+}
+
+/**
+ * 带前缀的字面量: 原始字符串 r"..." r#"..."#，字节字符串 b"..." br"..."，C 字符串 c"..."，字节 b'a'
+ * 不是这些字面量(如普通的标识符 r、b)时返回 None
+ */
+fn prefixed_literal(s: &str) -> Option<(Kind, usize)> {
+    let prefix = ["br", "cr", "r", "b", "c"]
+        .into_iter()
+        .find(|p| s.starts_with(p))?;
+    let after = &s[prefix.len()..];
+    let raw = prefix.ends_with('r');
+
+    if raw && (after.starts_with('"') || after.starts_with("#\"") || after.starts_with("##")) {
+        Some((Kind::Str, raw_string(s, prefix.len())))
+    } else if !raw && after.starts_with('"') {
+        Some((Kind::Str, string(s, prefix.len())))
+    } else if prefix == "b" && after.starts_with('\'') {
+        let (kind, len) = quote(after);
+        Some((kind, prefix.len() + len))
+    } else {
+        None
+    }
+}
+
+/**
+ * 单引号开头的可能是字符 'a' '\n' '😻'，也可能是生命周期 'a 'static
+ * 引号后面只有一个字符(或一个转义)并且紧跟着引号时是字符，否则是生命周期
+ */
+fn quote(s: &str) -> (Kind, usize) {
+    let mut chars = s[1..].char_indices();
+    match chars.next() {
+        Some((_, '\\')) => {
+            // 转义的字符，如 '\n' '\'' '\u{1F600}'
+            let end = s[2..]
+                .char_indices()
+                .skip(1)
+                .find(|&(_, c)| c == '\'')
+                .map_or(s.len(), |(i, _)| 2 + i + 1);
+            (Kind::Char, end)
+        }
+        Some((i, c)) => {
+            let next = 1 + i + c.len_utf8();
+            if s[next..].starts_with('\'') {
+                (Kind::Char, next + 1)
+            } else if c == '_' || c.is_alphabetic() {
+                (Kind::Lifetime, 1 + ident(&s[1..]))
+            } else {
+                (Kind::Punct, 1)
+            }
+        }
+        None => (Kind::Punct, 1),
+    }
+}
+
+/**
+ * 数字字面量，包括下划线分隔 100_000、进制前缀 0xff、小数和指数 1.5e-3、类型后缀 1.0f32 5u8
+ * 1..5 和 1.max(2) 中的点不属于数字
+ */
+fn number(s: &str) -> usize {
+    let hex = s.starts_with("0x") || s.starts_with("0X");
+    let mut i = take_while(s, |c| c == '_' || c.is_ascii_alphanumeric());
+
+    let after = &s[i..];
+    let fraction = after.starts_with('.')
+        && after[1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_digit());
+    if !hex && fraction {
+        i += 1 + take_while(&after[1..], |c| c == '_' || c.is_ascii_alphanumeric());
+    }
+    // 指数的符号，如 1e-3，前面的 1e 已经作为字母数字读取了
+    // e 之前只能是数字、下划线和小数点，10usize-1 中 usize 的 e 不是指数
+    let exponent = s[..i].strip_suffix(['e', 'E']).is_some_and(|m| {
+        m.chars()
+            .all(|c| c == '_' || c == '.' || c.is_ascii_digit())
+    });
+    if !hex && exponent {
+        let after = &s[i..];
+        if (after.starts_with('+') || after.starts_with('-'))
+            && after[1..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_digit())
+        {
+            i += 1 + take_while(&after[1..], |c| c == '_' || c.is_ascii_alphanumeric());
+        }
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 去掉空白之后的词法单元
+    fn tokens(source: &str) -> Vec<(Kind, &str)> {
+        tokenize(source)
+            .into_iter()
+            .filter(|t| t.kind != Kind::Whitespace)
+            .map(|t| (t.kind, t.text))
+            .collect()
+    }
+
+    #[test]
+    fn tokens_cover_source() {
+        let source = "fn main() { let s = r#\"a \"b\"\"#; /* x */ }";
+        let text: String = tokenize(source).iter().map(|t| t.text).collect();
+        assert_eq!(text, source);
+    }
+
+    #[test]
+    fn lifetimes() {
+        assert_eq!(
+            tokens("&'a str"),
+            [
+                (Kind::Punct, "&"),
+                (Kind::Lifetime, "'a"),
+                (Kind::Ident, "str")
+            ]
+        );
+        assert_eq!(
+            tokens("&'static str"),
+            [
+                (Kind::Punct, "&"),
+                (Kind::Lifetime, "'static"),
+                (Kind::Ident, "str")
+            ]
+        );
+    }
+
+    #[test]
+    fn chars() {
+        assert_eq!(tokens("'😻'"), [(Kind::Char, "'😻'")]);
+        assert_eq!(tokens("'a'"), [(Kind::Char, "'a'")]);
+        assert_eq!(tokens("'\\''"), [(Kind::Char, "'\\''")]);
+        assert_eq!(tokens("b'a'"), [(Kind::Char, "b'a'")]);
+    }
+
+    #[test]
+    fn raw_strings() {
+        assert_eq!(
+            tokens("r#\"say \"hi\"\"# + 1"),
+            [
+                (Kind::Str, "r#\"say \"hi\"\"#"),
+                (Kind::Punct, "+"),
+                (Kind::Number, "1")
+            ]
+        );
+        assert_eq!(tokens("r\"a\\b\""), [(Kind::Str, "r\"a\\b\"")]);
+        // 没有引号的 r 和 b 是普通的标识符
+        assert_eq!(tokens("r b"), [(Kind::Ident, "r"), (Kind::Ident, "b")]);
+    }
+
+    #[test]
+    fn nested_block_comments() {
+        assert_eq!(
+            tokens("/* a /* b */ c */ x"),
+            [
+                (Kind::BlockComment, "/* a /* b */ c */"),
+                (Kind::Ident, "x")
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(tokens("1.0f32"), [(Kind::Number, "1.0f32")]);
+        assert_eq!(tokens("100_000"), [(Kind::Number, "100_000")]);
+        assert_eq!(tokens("1.5e-3"), [(Kind::Number, "1.5e-3")]);
+        assert_eq!(tokens("2E+10"), [(Kind::Number, "2E+10")]);
+        assert_eq!(tokens("0xff"), [(Kind::Number, "0xff")]);
+        assert_eq!(
+            tokens("1..5"),
+            [
+                (Kind::Number, "1"),
+                (Kind::Punct, "."),
+                (Kind::Punct, "."),
+                (Kind::Number, "5")
+            ]
+        );
+    }
+
+    #[test]
+    fn suffix_e_is_not_exponent() {
+        assert_eq!(
+            tokens("10usize-1"),
+            [
+                (Kind::Number, "10usize"),
+                (Kind::Punct, "-"),
+                (Kind::Number, "1")
+            ]
+        );
+        assert_eq!(
+            tokens("5isize+1"),
+            [
+                (Kind::Number, "5isize"),
+                (Kind::Punct, "+"),
+                (Kind::Number, "1")
+            ]
+        );
+        assert_eq!(
+            tokens("0x1e-1"),
+            [
+                (Kind::Number, "0x1e"),
+                (Kind::Punct, "-"),
+                (Kind::Number, "1")
+            ]
+        );
+    }
+}
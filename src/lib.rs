@@ -0,0 +1,95 @@
+//! Rust 学习笔记的课程库 Library crate
+//!
+//! 每个章节是一个 base_XX 模块，章节中用于讲解的类型和函数在这里重新导出，
+//! 可以在测试、基准测试或者其他项目中直接使用，如 `rust_learn::Circle`
+//! 章节通过 Lesson 特征登记到 registry 中，运行时输出写到 Output，需要输入时从 Input 读取
+//!
+//! 运行、快照、练习、测验等功能也在库中，但不属于公开的 API，
+//! rust-learn 程序只安装分配计数器并调用 main
+
+#[macro_use]
+mod i18n;
+#[macro_use]
+mod output;
+
+// 课程章节中保留了一些刻意的写法用于演示(如冗余的字段初始化、未读取的字段)，不做 clippy 检查
+// 每个章节由同名的 feature 控制是否编译，见 Cargo.toml
+#[cfg(feature = "base_01")]
+#[allow(dead_code, clippy::all)]
+pub mod base_01;
+#[cfg(feature = "base_02")]
+#[allow(dead_code, clippy::all)]
+pub mod base_02;
+#[cfg(feature = "base_03")]
+#[allow(dead_code, clippy::all)]
+pub mod base_03;
+#[cfg(feature = "base_04")]
+#[allow(dead_code, clippy::all)]
+pub mod base_04;
+#[cfg(feature = "base_05")]
+#[allow(dead_code, clippy::all)]
+pub mod base_05;
+#[cfg(feature = "base_06")]
+#[allow(dead_code, clippy::all)]
+pub mod base_06;
+#[cfg(feature = "base_07")]
+#[allow(dead_code, clippy::all)]
+pub mod base_07;
+#[cfg(feature = "base_08")]
+#[allow(dead_code, clippy::all)]
+pub mod base_08;
+#[cfg(feature = "base_09")]
+#[allow(dead_code, clippy::all)]
+pub mod base_09;
+#[cfg(feature = "base_10")]
+#[allow(dead_code, clippy::all)]
+pub mod base_10;
+#[cfg(feature = "base_11")]
+#[allow(dead_code, clippy::all)]
+pub mod base_11;
+
+// 课程的运行、快照、练习、测验等功能和 rust-learn 的各个命令，只在库内部使用
+mod book;
+mod cli;
+mod compile_fail;
+mod exercise;
+mod explain;
+mod highlight;
+mod input;
+mod lesson;
+mod lexer;
+mod log;
+mod progress;
+mod quiz;
+mod report;
+mod runner;
+mod rustc;
+mod search;
+mod serve;
+mod shell;
+mod snapshot;
+mod source;
+mod stats;
+mod watch;
+
+// 各章节中用于讲解的类型和函数，章节没有编译时不导出
+#[cfg(feature = "base_04")]
+pub use base_04::{build_user, User};
+#[cfg(feature = "base_06")]
+pub use base_06::{plus_one, Coin, UsState};
+#[cfg(feature = "base_07")]
+pub use base_07::Circle;
+#[cfg(feature = "base_08")]
+pub use base_08::{largest, largest_t, Pair, Point, PointAdd, Pointu, Post, Summary};
+#[cfg(feature = "base_10")]
+pub use base_10::longest;
+#[cfg(feature = "base_11")]
+pub use base_11::read_username_from_file;
+
+pub use input::Input;
+pub use lesson::{registry, Each, Lesson, Section};
+pub use output::Output;
+
+// rust-learn 程序的入口和 run --stats 使用的分配计数器
+pub use cli::main;
+pub use stats::Counting;
@@ -0,0 +1,281 @@
+// 分级日志 Leveled logging
+
+use std::{
+    env,
+    io::{self, Write},
+    sync::RwLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::progress;
+
+/**
+ * 日志级别，从低到高
+ *
+ * Trace: 运行过程，如每个课程函数的开始和结束，只有 -v 时显示
+ * Debug: 调试输出，对应 dbg!(章节中的 out_dbg!)
+ * Info:  章节开始和结束的横幅
+ * Warn:  不影响命令本身的问题，如进度文件无法写入
+ * Error: 出错，如章节 panic
+ *
+ * 课程本身的输出(out! / outln!)不是日志，总是显示
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+const LEVELS: [Level; 5] = [
+    Level::Trace,
+    Level::Debug,
+    Level::Info,
+    Level::Warn,
+    Level::Error,
+];
+
+impl Level {
+    pub fn parse(s: &str) -> Option<Level> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "trace" => Some(Level::Trace),
+            "debug" => Some(Level::Debug),
+            "info" => Some(Level::Info),
+            "warn" => Some(Level::Warn),
+            "error" => Some(Level::Error),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+/**
+ * 日志的过滤规则和格式
+ *
+ * level:      没有单独指定的 target 使用的最低级别，默认是 Debug，与原来 dbg! 总是输出一致
+ * targets:    按 target 单独指定的最低级别，target 是章节 id(如 base_04)或者功能名(如 progress)
+ * timestamps: 每条日志前面加上时间、级别和 target
+ */
+#[derive(Clone)]
+pub struct Filter {
+    level: Level,
+    targets: Vec<(String, Level)>,
+    timestamps: bool,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter {
+            level: Level::Debug,
+            targets: Vec::new(),
+            timestamps: false,
+        }
+    }
+}
+
+impl Filter {
+    /**
+     * 解析过滤规则，逗号分隔，每一项是级别或者 target=级别，如 warn,base_04=trace
+     * 格式不对时返回 None
+     */
+    pub fn parse(spec: &str) -> Option<Filter> {
+        let mut filter = Filter::default();
+        for item in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match item.split_once('=') {
+                Some((target, level)) => {
+                    let level = Level::parse(level)?;
+                    filter.targets.retain(|(t, _)| t != target.trim());
+                    filter.targets.push((target.trim().to_string(), level));
+                }
+                None => filter.level = Level::parse(item)?,
+            }
+        }
+        Some(filter)
+    }
+
+    // 与 parse 相反，生成过滤规则的文字形式，如 warn,base_04=trace，用于传给子进程
+    pub fn spec(&self) -> String {
+        let mut items = vec![self.level.label().to_ascii_lowercase()];
+        for (target, level) in &self.targets {
+            items.push(format!("{}={}", target, level.label().to_ascii_lowercase()));
+        }
+        items.join(",")
+    }
+
+    pub fn timestamps(&self) -> bool {
+        self.timestamps
+    }
+
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        let min = self
+            .targets
+            .iter()
+            .find(|(t, _)| t == target)
+            .map_or(self.level, |(_, level)| *level);
+        level >= min
+    }
+
+    /**
+     * 日志的前缀，只有打开了时间戳时才有，如 2026-10-18 08:00:01.234 INFO  base_01:
+     * 没有时间戳时日志和原来的输出完全一样，如横幅仍然是 ========== base_01: 变量 开始 ==========
+     */
+    pub fn prefix(&self, level: Level, target: &str) -> Option<String> {
+        if !self.timestamps {
+            return None;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let secs = now.as_secs();
+        Some(format!(
+            "{} {:02}:{:02}:{:02}.{:03} {:<5} {}: ",
+            progress::format_date(secs),
+            secs / 3600 % 24,
+            secs / 60 % 60,
+            secs % 60,
+            now.subsec_millis(),
+            level.label(),
+            target
+        ))
+    }
+}
+
+/**
+ * 当前的过滤规则，None 表示还没有设置，第一次使用时从环境变量 RUST_LEARN_LOG 中读取
+ * 环境变量的格式见 Filter::parse，格式不对时使用默认规则
+ */
+static FILTER: RwLock<Option<Filter>> = RwLock::new(None);
+
+// 在当前的过滤规则上调用 f，只在读锁中借用，不复制规则
+fn with_filter<R>(f: impl FnOnce(&Filter) -> R) -> R {
+    if let Some(filter) = FILTER.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return f(filter);
+    }
+    let filter = env::var("RUST_LEARN_LOG")
+        .ok()
+        .and_then(|spec| Filter::parse(&spec))
+        .unwrap_or_default();
+    let result = f(&filter);
+    set_filter(filter);
+    result
+}
+
+pub fn filter() -> Filter {
+    with_filter(Filter::clone)
+}
+
+pub fn set_filter(filter: Filter) {
+    *FILTER.write().unwrap_or_else(|e| e.into_inner()) = Some(filter);
+}
+
+/**
+ * 按 -v / -q 调整默认级别，verbosity 为正时每次降低一级，为负时每次提高一级
+ * 默认 Debug，-v 显示 Trace，-q 隐藏调试输出，-qq 再隐藏横幅，-qqq 只显示错误
+ */
+pub fn adjust(verbosity: i32) {
+    let mut filter = filter();
+    let index = LEVELS.iter().position(|l| *l == filter.level).unwrap_or(1) as i32;
+    let index = (index - verbosity).clamp(0, LEVELS.len() as i32 - 1);
+    filter.level = LEVELS[index as usize];
+    set_filter(filter);
+}
+
+pub fn set_timestamps(timestamps: bool) {
+    let mut filter = filter();
+    filter.timestamps = timestamps;
+    set_filter(filter);
+}
+
+pub fn enabled(level: Level, target: &str) -> bool {
+    with_filter(|filter| filter.enabled(level, target))
+}
+
+// 按当前的过滤规则生成日志前缀，见 Filter::prefix
+pub fn prefix(level: Level, target: &str) -> Option<String> {
+    with_filter(|filter| filter.prefix(level, target))
+}
+
+/**
+ * 章节之外(没有 Output)的日志，写到 stderr
+ */
+pub fn log(level: Level, target: &str, message: &str) {
+    if !enabled(level, target) {
+        return;
+    }
+    let prefix = prefix(level, target).unwrap_or_default();
+    let _ = writeln!(io::stderr(), "{}{}", prefix, message);
+}
+
+pub fn warn(target: &str, message: &str) {
+    log(Level::Warn, target, message);
+}
+
+pub fn error(target: &str, message: &str) {
+    log(Level::Error, target, message);
+}
+
+/**
+ * 宏所在模块的 target，取 module_path! 的最后一段，章节中就是章节 id，如 base_04
+ * 只有 out_dbg! 使用，只编译部分章节时可能用不到
+ */
+#[allow(dead_code)]
+pub fn target(module_path: &'static str) -> &'static str {
+    module_path.rsplit("::").next().unwrap_or(module_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_default_and_target_levels() {
+        let filter = Filter::parse("warn, base_04=TRACE,progress=error").unwrap();
+        assert!(filter.enabled(Level::Warn, "base_01"));
+        assert!(!filter.enabled(Level::Info, "base_01"));
+        assert!(filter.enabled(Level::Trace, "base_04"));
+        assert!(!filter.enabled(Level::Warn, "progress"));
+        assert_eq!(filter.spec(), "warn,base_04=trace,progress=error");
+
+        // 同一个 target 写了多次时以最后一次为准
+        let filter = Filter::parse("base_04=trace,base_04=info").unwrap();
+        assert_eq!(filter.spec(), "debug,base_04=info");
+    }
+
+    #[test]
+    fn parse_empty_spec_is_the_default() {
+        let filter = Filter::parse("").unwrap();
+        assert_eq!(filter.spec(), Filter::default().spec());
+        assert!(filter.enabled(Level::Debug, "base_01"));
+        assert!(!filter.enabled(Level::Trace, "base_01"));
+        assert!(!filter.timestamps());
+    }
+
+    #[test]
+    fn parse_rejects_unknown_levels() {
+        assert!(Filter::parse("loud").is_none());
+        assert!(Filter::parse("warn,base_04=").is_none());
+        assert!(Filter::parse("base_04=verbose").is_none());
+    }
+
+    #[test]
+    fn spec_round_trips() {
+        for spec in [
+            "trace",
+            "error,base_01=debug",
+            "info,base_04=warn,progress=trace",
+        ] {
+            assert_eq!(Filter::parse(spec).unwrap().spec(), spec);
+        }
+    }
+}
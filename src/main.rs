@@ -1,45 +1,14 @@
-mod base_01;
-mod base_02;
-mod base_03;
-mod base_04;
-mod base_05;
-mod base_06;
-mod base_07;
-mod base_08;
-mod base_09;
-mod base_10;
-mod base_11;
+// rust-learn 命令行程序 Command line entry, built on the rust_learn library
 
-fn main() {
-    // base_01: 变量 variable
-    base_01::main();
+use std::process::ExitCode;
 
-    // base_02: 基础类型 base type
-    base_02::main();
+use rust_learn::Counting;
 
-    // base_03: 所有权和借用 ownership borrowing
-    base_03::main();
+// 统计 run --stats 中的堆分配，见 rust_learn::Counting
+#[global_allocator]
+static GLOBAL: Counting = Counting;
 
-    // base_04: 复合类型 quote type
-    base_04::main();
-
-    // base_05: 流程控制 flow control
-    base_05::main();
-
-    // base_06: 模式匹配 pattern matching
-    base_06::main();
-
-    // base_07: 方法 method
-    base_07::main();
-
-    // base_08: 泛型和特征 generics and trait
-    base_08::main();
-
-    // base_09: 集合类型 collection
-    base_09::main();
-
-    // base_10: 生命周期 life cycle
-    base_10::main();
-
-    base_11::main();
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    rust_learn::main(&args)
 }
@@ -0,0 +1,156 @@
+// 课程输出 Lesson output
+
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+use crate::{
+    highlight,
+    log::{self, Filter, Level},
+    stats,
+};
+
+/**
+ * 课程函数不再直接 println!，而是写到调用方传入的 Output 中
+ * 这样调用方可以决定输出去哪里：控制台、文件、内存缓冲区...
+ *
+ * out:   普通输出，对应 println! / print!
+ * debug: 调试输出，对应 dbg!，为 None 时和普通输出写到同一个地方
+ *        Info 之外的日志也写到这里，Info 级别的横幅和普通输出写在一起
+ * color: 是否可以输出 ANSI 颜色，只有输出到终端时才是 true
+ * filter: 日志的过滤规则，为 None 时使用全局的规则(见 log::filter)
+ */
+pub struct Output<'a> {
+    out: Box<dyn Write + 'a>,
+    debug: Option<Box<dyn Write + 'a>>,
+    color: bool,
+    filter: Option<Filter>,
+}
+
+impl Output<'static> {
+    // 与原来的行为一致：普通输出到 stdout，dbg! 输出到 stderr
+    pub fn console() -> Self {
+        Output {
+            out: Box::new(io::stdout()),
+            debug: Some(Box::new(io::stderr())),
+            color: highlight::enabled(),
+            filter: None,
+        }
+    }
+}
+
+impl<'a> Output<'a> {
+    // 所有输出(包括 dbg 输出)按顺序写入同一个 writer，如 Vec<u8>
+    pub fn new(writer: impl Write + 'a) -> Self {
+        Output {
+            out: Box::new(writer),
+            debug: None,
+            color: false,
+            filter: None,
+        }
+    }
+
+    // 使用指定的日志规则，不受 -v / -q、--log 和环境变量影响，如快照总是使用默认规则
+    pub(crate) fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn color(&self) -> bool {
+        self.color
+    }
+
+    // 写入时的分配(如缓冲区扩容)属于输出本身，不计入课程的统计，见 stats::untracked
+    pub fn print(&mut self, args: fmt::Arguments) {
+        if let Err(e) = stats::untracked(|| self.out.write_fmt(args)) {
+            panic!("failed printing to output: {}", e);
+        }
+    }
+
+    pub fn debug(&mut self, args: fmt::Arguments) {
+        let w = self.debug.as_mut().unwrap_or(&mut self.out);
+        if let Err(e) = stats::untracked(|| w.write_fmt(args)) {
+            panic!("failed printing to output: {}", e);
+        }
+    }
+
+    /**
+     * 写一条日志，级别低于过滤规则(见 log::Filter)时不输出
+     * target 是章节 id 等日志来源，用于过滤和加在时间戳后面
+     */
+    pub(crate) fn log(&mut self, level: Level, target: &str, args: fmt::Arguments) {
+        stats::untracked(|| self.write_log(level, target, args));
+    }
+
+    fn write_log(&mut self, level: Level, target: &str, args: fmt::Arguments) {
+        let enabled = match &self.filter {
+            Some(filter) => filter.enabled(level, target),
+            None => log::enabled(level, target),
+        };
+        if !enabled {
+            return;
+        }
+        let prefix = match &self.filter {
+            Some(filter) => filter.prefix(level, target),
+            None => log::prefix(level, target),
+        }
+        .unwrap_or_default();
+        if level == Level::Info {
+            self.print(format_args!("{}{}", prefix, args));
+        } else {
+            self.debug(format_args!("{}{}", prefix, args));
+        }
+    }
+}
+
+/**
+ * 对应 print!，第一个参数是 Output
+ */
+#[macro_export]
+macro_rules! out {
+    ($out:expr, $($arg:tt)*) => {
+        $out.print(format_args!($($arg)*))
+    };
+}
+
+/**
+ * 对应 println!，第一个参数是 Output
+ */
+#[macro_export]
+macro_rules! outln {
+    ($out:expr) => {
+        $out.print(format_args!("\n"))
+    };
+    ($out:expr, $($arg:tt)*) => {
+        $out.print(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/**
+ * 对应 dbg!，输出格式与 dbg! 相同，同样会返回表达式的值
+ * 作为 Debug 级别的日志输出，-q 时不显示
+ * 只在章节中使用，只编译部分章节时可能用不到
+ */
+#[allow(unused_macros)]
+macro_rules! out_dbg {
+    ($out:expr, $val:expr) => {
+        match $val {
+            tmp => {
+                $out.log(
+                    $crate::log::Level::Debug,
+                    $crate::log::target(module_path!()),
+                    format_args!(
+                        "[{}:{}:{}] {} = {:#?}\n",
+                        file!(),
+                        line!(),
+                        column!(),
+                        stringify!($val),
+                        &tmp
+                    ),
+                );
+                tmp
+            }
+        }
+    };
+}
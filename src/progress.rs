@@ -0,0 +1,539 @@
+// 学习进度 Learner progress
+
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{exercise, lesson, log};
+
+/**
+ * 进度文件的格式版本，格式有不兼容的修改时加一
+ *
+ * 进度文件是文本格式，第一行是版本，之后每行一条记录:
+ *
+ * rust-learn progress 2
+ * chapter base_01 completed 1760000000
+ * exercise base_03_borrow attempted 1760000100
+ * quiz base_02 2/3 3/3 1760000200
+ *
+ * 最后一列是最近一次尝试的时间(unix 秒)，测验记录的是最近一次和最好的一次得分
+ * 版本 1 没有测验记录，可以直接按版本 2 读取
+ */
+const VERSION: u32 = 2;
+const HEADER: &str = "rust-learn progress";
+
+/**
+ * 进度文件所在的目录
+ *
+ * 可以通过环境变量 RUST_LEARN_DATA_DIR 指定，否则使用系统的用户数据目录:
+ * Linux:   $XDG_DATA_HOME/rust-learn 或 ~/.local/share/rust-learn
+ * macOS:   ~/Library/Application Support/rust-learn
+ * Windows: %APPDATA%\rust-learn
+ */
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("RUST_LEARN_DATA_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.map(|h| h.join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home.map(|h| h.join(".local/share")))
+    };
+    base.map(|dir| dir.join("rust-learn"))
+}
+
+pub fn state_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("progress.txt"))
+}
+
+/**
+ * 一个章节或练习的进度
+ * completed:    是否完成过，完成之后再次失败也仍然算完成
+ * last_attempt: 最近一次尝试的时间(unix 秒)
+ */
+#[derive(Clone, Copy)]
+pub struct Record {
+    pub completed: bool,
+    pub last_attempt: u64,
+}
+
+/**
+ * 一个章节测验的得分
+ * last:         最近一次答对的题数和总题数
+ * best:         答对比例最高的一次，题目增减之后仍然保留原来的总题数
+ * last_attempt: 最近一次测验的时间(unix 秒)
+ */
+#[derive(Clone, Copy)]
+pub struct Score {
+    pub last: (usize, usize),
+    pub best: (usize, usize),
+    pub last_attempt: u64,
+}
+
+#[derive(Default)]
+pub struct Progress {
+    chapters: BTreeMap<String, Record>,
+    exercises: BTreeMap<String, Record>,
+    quizzes: BTreeMap<String, Score>,
+}
+
+/**
+ * 读取进度文件的结果
+ *
+ * 文件不存在时是空的进度
+ * 文件损坏时(版本不认识、有无法解析的行)，能解析的记录照常读取，
+ * 原文件备份为 progress.txt.bak，recovered 为备份的路径
+ */
+pub struct Loaded {
+    pub progress: Progress,
+    pub recovered: Option<PathBuf>,
+}
+
+impl Progress {
+    pub fn load(path: &Path) -> io::Result<Loaded> {
+        let text = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(Loaded {
+                    progress: Progress::default(),
+                    recovered: None,
+                })
+            }
+            Err(e) => return Err(e),
+        };
+
+        let (progress, intact) = Progress::parse(&text);
+        let recovered = if intact {
+            None
+        } else {
+            let backup = path.with_extension("txt.bak");
+            fs::copy(path, &backup)?;
+            Some(backup)
+        };
+        Ok(Loaded {
+            progress,
+            recovered,
+        })
+    }
+
+    // 解析进度文件，返回能解析的记录以及文件是否完好
+    fn parse(text: &str) -> (Progress, bool) {
+        let mut progress = Progress::default();
+        let mut lines = text.lines();
+
+        let version = lines
+            .next()
+            .and_then(|line| line.strip_prefix(HEADER))
+            .and_then(|v| v.trim().parse::<u32>().ok());
+        if !version.is_some_and(|v| (1..=VERSION).contains(&v)) {
+            return (progress, false);
+        }
+
+        let mut intact = true;
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if let ["quiz", id, last, best, time] = fields[..] {
+                match (fraction(last), fraction(best), time.parse()) {
+                    (Some(last), Some(best), Ok(last_attempt)) => {
+                        let score = Score {
+                            last,
+                            best,
+                            last_attempt,
+                        };
+                        progress.quizzes.insert(id.to_string(), score);
+                    }
+                    _ => intact = false,
+                }
+                continue;
+            }
+            let record = match fields[..] {
+                [kind, id, status, time] => {
+                    let completed = match status {
+                        "completed" => Some(true),
+                        "attempted" => Some(false),
+                        _ => None,
+                    };
+                    match (completed, time.parse()) {
+                        (Some(completed), Ok(last_attempt)) => Some((
+                            kind,
+                            id,
+                            Record {
+                                completed,
+                                last_attempt,
+                            },
+                        )),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            match record {
+                Some(("chapter", id, record)) => {
+                    progress.chapters.insert(id.to_string(), record);
+                }
+                Some(("exercise", id, record)) => {
+                    progress.exercises.insert(id.to_string(), record);
+                }
+                _ => intact = false,
+            }
+        }
+        (progress, intact)
+    }
+
+    /**
+     * 写入进度文件
+     * 先写到临时文件再重命名，写到一半被打断时不会留下损坏的进度文件
+     */
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = format!("{} {}\n", HEADER, VERSION);
+        for (kind, records) in [("chapter", &self.chapters), ("exercise", &self.exercises)] {
+            for (id, record) in records {
+                let status = if record.completed {
+                    "completed"
+                } else {
+                    "attempted"
+                };
+                text.push_str(&format!(
+                    "{} {} {} {}\n",
+                    kind, id, status, record.last_attempt
+                ));
+            }
+        }
+        for (id, score) in &self.quizzes {
+            text.push_str(&format!(
+                "quiz {} {}/{} {}/{} {}\n",
+                id, score.last.0, score.last.1, score.best.0, score.best.1, score.last_attempt
+            ));
+        }
+
+        let tmp = path.with_extension("txt.tmp");
+        fs::write(&tmp, text)?;
+        fs::rename(&tmp, path)
+    }
+
+    pub fn record_chapter(&mut self, id: &str, completed: bool) {
+        update_record(&mut self.chapters, id, completed);
+    }
+
+    pub fn record_exercise(&mut self, id: &str, completed: bool) {
+        update_record(&mut self.exercises, id, completed);
+    }
+
+    // 记录一次测验的得分，答对的比例比以前高时同时更新最好成绩
+    pub fn record_quiz(&mut self, id: &str, correct: usize, total: usize) {
+        let last = (correct, total);
+        let score = self.quizzes.entry(id.to_string()).or_insert(Score {
+            last,
+            best: last,
+            last_attempt: 0,
+        });
+        let (best, best_total) = score.best;
+        if correct * best_total > best * total || best_total == 0 {
+            score.best = last;
+        }
+        score.last = last;
+        score.last_attempt = now();
+    }
+
+    pub fn chapter(&self, id: &str) -> Option<Record> {
+        self.chapters.get(id).copied()
+    }
+
+    pub fn exercise(&self, id: &str) -> Option<Record> {
+        self.exercises.get(id).copied()
+    }
+
+    pub fn quiz(&self, id: &str) -> Option<Score> {
+        self.quizzes.get(id).copied()
+    }
+}
+
+/**
+ * 读取进度文件，修改之后写回
+ * 进度只是辅助信息，找不到用户数据目录或者读写失败时只打印警告，不影响命令本身
+ */
+pub fn update(f: impl FnOnce(&mut Progress)) {
+    let Some(path) = state_path() else {
+        return;
+    };
+    let Some(mut progress) = load_or_warn(&path) else {
+        return;
+    };
+    f(&mut progress);
+    if let Err(e) = progress.save(&path) {
+        let message = tr!("cli.cannot_write", path.display(), e);
+        log::warn("progress", &tr!("progress.warning", message));
+    }
+}
+
+// 读取进度文件，文件损坏时恢复之后立即写回，下次读取时就是完好的文件
+fn load_or_warn(path: &Path) -> Option<Progress> {
+    match Progress::load(path) {
+        Ok(loaded) => {
+            if let Some(backup) = loaded.recovered {
+                let message = tr!("progress.recovered", path.display(), backup.display());
+                log::warn("progress", &tr!("progress.warning", message));
+                let _ = loaded.progress.save(path);
+            }
+            Some(loaded.progress)
+        }
+        Err(e) => {
+            let message = tr!("cli.cannot_read", path.display(), e);
+            log::warn("progress", &tr!("progress.warning", message));
+            None
+        }
+    }
+}
+
+// 按课程顺序列出每个章节是否完成、章节练习的完成数、测验的最好成绩以及最近一次尝试的日期
+pub fn print() {
+    let Some(path) = state_path() else {
+        eprintln!("{}", tr!("progress.no_data_dir"));
+        return;
+    };
+    let progress = load_or_warn(&path).unwrap_or_default();
+    let exercises = exercise::exercises().unwrap_or_default();
+
+    // 章节状态按当前语言中最长的状态对齐
+    let statuses = [
+        tr!("progress.completed"),
+        tr!("progress.attempted"),
+        tr!("progress.not_started"),
+    ];
+    let width = statuses
+        .iter()
+        .map(|s| s.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut chapters_done = 0;
+    let mut exercises_done = 0;
+    for lesson in lesson::registry() {
+        let chapter = progress.chapter(lesson.id());
+        let status = match chapter {
+            Some(record) if record.completed => {
+                chapters_done += 1;
+                &statuses[0]
+            }
+            Some(_) => &statuses[1],
+            None => &statuses[2],
+        };
+
+        let mut done = 0;
+        let mut total = 0;
+        let mut last_attempt = chapter.map(|r| r.last_attempt);
+        for exercise in exercises.iter().filter(|e| e.chapter == lesson.id()) {
+            total += 1;
+            if let Some(record) = progress.exercise(&exercise.id) {
+                done += usize::from(record.completed);
+                last_attempt = last_attempt.max(Some(record.last_attempt));
+            }
+        }
+        exercises_done += done;
+
+        let quiz = progress.quiz(lesson.id());
+        let score = match quiz {
+            Some(score) => format!("{:<5}", format!("{}/{}", score.best.0, score.best.1)),
+            None => format!("{:<5}", "-"),
+        };
+        last_attempt = last_attempt.max(quiz.map(|s| s.last_attempt));
+
+        let date = last_attempt
+            .map(format_date)
+            .unwrap_or_else(|| String::from("-"));
+        println!(
+            "{}  {:<width$}  {}  {}  {:<10}  {}",
+            lesson.id(),
+            status,
+            tr!("progress.exercises", done, total),
+            tr!("progress.quiz", score),
+            date,
+            lesson.title()
+        );
+    }
+    println!(
+        "{}",
+        tr!(
+            "progress.totals",
+            chapters_done,
+            lesson::registry().len(),
+            exercises_done,
+            exercises.len()
+        )
+    );
+    println!("{}", tr!("progress.saved_in", path.display()));
+}
+
+// 解析 2/3 这样的得分
+fn fraction(s: &str) -> Option<(usize, usize)> {
+    let (correct, total) = s.split_once('/')?;
+    let (correct, total) = (correct.parse().ok()?, total.parse().ok()?);
+    (correct <= total).then_some((correct, total))
+}
+
+fn update_record(records: &mut BTreeMap<String, Record>, id: &str, completed: bool) {
+    let record = records.entry(id.to_string()).or_insert(Record {
+        completed: false,
+        last_attempt: 0,
+    });
+    record.completed |= completed;
+    record.last_attempt = now();
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/**
+ * unix 秒转换为 UTC 日期，如 2025-10-18
+ * 按公历从 0000-03-01 开始推算，见 http://howardhinnant.github.io/date_algorithms.html
+ */
+pub fn format_date(secs: u64) -> String {
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 每个测试使用单独的临时目录，测试之间并行运行也不会互相影响
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("rust-learn-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parse_reads_all_record_kinds() {
+        let text = "rust-learn progress 2\n\
+                    chapter base_01 completed 1760000000\n\
+                    exercise base_03_borrow attempted 1760000100\n\
+                    quiz base_02 2/3 3/3 1760000200\n";
+        let (progress, intact) = Progress::parse(text);
+        assert!(intact);
+
+        let chapter = progress.chapter("base_01").unwrap();
+        assert!(chapter.completed);
+        assert_eq!(chapter.last_attempt, 1_760_000_000);
+        let exercise = progress.exercise("base_03_borrow").unwrap();
+        assert!(!exercise.completed);
+        let quiz = progress.quiz("base_02").unwrap();
+        assert_eq!((quiz.last, quiz.best), ((2, 3), (3, 3)));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_version() {
+        for header in ["rust-learn progress 3", "rust-learn progress 0", "garbage"] {
+            let text = format!("{}\nchapter base_01 completed 1760000000\n", header);
+            let (progress, intact) = Progress::parse(&text);
+            assert!(!intact, "{}", header);
+            assert!(progress.chapter("base_01").is_none());
+        }
+        assert!(!Progress::parse("").1);
+    }
+
+    #[test]
+    fn parse_keeps_good_lines_around_bad_ones() {
+        let text = "rust-learn progress 2\n\
+                    chapter base_01 completed 1760000000\n\
+                    chapter base_02 finished 1760000000\n\
+                    not a record\n\
+                    quiz base_03 4/3 4/3 1760000000\n\
+                    exercise base_03_borrow completed 1760000100\n";
+        let (progress, intact) = Progress::parse(text);
+        assert!(!intact);
+        assert!(progress.chapter("base_01").is_some());
+        assert!(progress.chapter("base_02").is_none());
+        assert!(progress.quiz("base_03").is_none());
+        assert!(progress.exercise("base_03_borrow").unwrap().completed);
+    }
+
+    #[test]
+    fn version_1_reads_as_version_2() {
+        let text = "rust-learn progress 1\n\
+                    chapter base_01 completed 1760000000\n\
+                    exercise base_03_borrow attempted 1760000100\n";
+        let (mut progress, intact) = Progress::parse(text);
+        assert!(intact);
+        assert!(progress.chapter("base_01").unwrap().completed);
+
+        // 保存时升级为当前版本，记录保持不变
+        let dir = temp_dir("progress-v1");
+        let path = dir.join("progress.txt");
+        progress.record_quiz("base_02", 1, 2);
+        progress.save(&path).unwrap();
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(saved.starts_with(&format!("{} {}\n", HEADER, VERSION)));
+        assert!(saved.contains("chapter base_01 completed 1760000000\n"));
+        assert!(saved.contains("exercise base_03_borrow attempted 1760000100\n"));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn load_backs_up_damaged_file() {
+        let dir = temp_dir("progress-damaged");
+        let path = dir.join("progress.txt");
+
+        let loaded = Progress::load(&path).unwrap();
+        assert!(loaded.recovered.is_none());
+
+        let damaged =
+            "rust-learn progress 2\nchapter base_01 completed 1760000000\n\u{0}\u{1}garbage\n";
+        fs::write(&path, damaged).unwrap();
+        let loaded = Progress::load(&path).unwrap();
+        let backup = loaded.recovered.unwrap();
+        assert_eq!(backup, dir.join("progress.txt.bak"));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), damaged);
+        assert!(loaded.progress.chapter("base_01").is_some());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn record_quiz_keeps_best_ratio() {
+        let mut progress = Progress::default();
+        progress.record_quiz("base_02", 2, 3);
+        progress.record_quiz("base_02", 1, 3);
+        let score = progress.quiz("base_02").unwrap();
+        assert_eq!((score.last, score.best), ((1, 3), (2, 3)));
+
+        // 题目增加之后，比例更高才算更好的成绩
+        progress.record_quiz("base_02", 2, 4);
+        assert_eq!(progress.quiz("base_02").unwrap().best, (2, 3));
+        progress.record_quiz("base_02", 4, 4);
+        assert_eq!(progress.quiz("base_02").unwrap().best, (4, 4));
+    }
+
+    #[test]
+    fn format_date_known_timestamps() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(86_399), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_760_000_000), "2025-10-09");
+        assert_eq!(format_date(4_102_444_799), "2099-12-31");
+    }
+}
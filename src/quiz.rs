@@ -0,0 +1,591 @@
+// 章节测验 Chapter quizzes
+
+use std::{collections::HashMap, io};
+
+use crate::{
+    compile_fail::{self, Check},
+    i18n::{self, Locale},
+    input::Input,
+    lesson::{self, Lesson},
+    log::Filter,
+    output::Output,
+    progress, runner,
+};
+
+/**
+ * 测验保存在仓库的 quizzes 目录下，每个章节一个文件，如 quizzes/base_02.txt，和消息目录一样编译时嵌入到程序中
+ * 文件中每道题以 [题目 id] 开头，之后每行一个 key = value，# 开头的行是注释:
+ *
+ * [f32_sum]
+ * question = f32 中 `0.1 + 0.2 == 0.3` 成立吗?
+ * question.en = Does `0.1 + 0.2 == 0.3` hold for f32?
+ * choice = 成立
+ * choice.en = it holds
+ * choice = 不成立
+ * choice.en = it does not hold
+ * answer = 1
+ * explain = f32 的精度较低，0.1 + 0.2 和 0.3 舍入到了同一个值
+ * output = base_02::float_type | 0.1 + 0.2: 3e99999a
+ * output = base_02::float_type | 0.3: 3e99999a
+ *
+ * 有 choice 的是选择题，answer 是正确选项的序号(从 1 开始)，choice.en 按顺序对应前面的 choice
+ * 没有 choice 的是填空题，answer 是可以接受的答案，多个答案用 | 分隔，比较之前都会规范化，见 normalize
+ * .en 结尾的是英文版本，没有时使用中文
+ *
+ * 答案的依据，quiz check 会逐条检查，保证答案和课程的实际行为一致:
+ * output = <课程函数> | <文本>  运行课程函数(中文输出)，输出中必须包含这段文本
+ * compile_fail = <反例>         compile_fail 目录下的反例必须仍然按预期编译失败
+ */
+const QUIZZES: &[(&str, &str)] = &[
+    ("base_01", include_str!("../quizzes/base_01.txt")),
+    ("base_02", include_str!("../quizzes/base_02.txt")),
+    ("base_03", include_str!("../quizzes/base_03.txt")),
+    ("base_04", include_str!("../quizzes/base_04.txt")),
+    ("base_05", include_str!("../quizzes/base_05.txt")),
+    ("base_06", include_str!("../quizzes/base_06.txt")),
+    ("base_07", include_str!("../quizzes/base_07.txt")),
+    ("base_08", include_str!("../quizzes/base_08.txt")),
+    ("base_09", include_str!("../quizzes/base_09.txt")),
+    ("base_10", include_str!("../quizzes/base_10.txt")),
+    ("base_11", include_str!("../quizzes/base_11.txt")),
+];
+
+// 中文和可选的英文版本，按当前语言选择
+#[derive(Default)]
+pub struct Text {
+    zh: String,
+    en: Option<String>,
+}
+
+impl Text {
+    fn new(zh: String) -> Self {
+        Text { zh, en: None }
+    }
+
+    pub fn get(&self) -> &str {
+        match (i18n::locale(), &self.en) {
+            (Locale::En, Some(en)) => en,
+            _ => &self.zh,
+        }
+    }
+}
+
+pub enum Answer {
+    // 正确选项的序号，从 1 开始
+    Choice(usize),
+    // 可以接受的答案，已经规范化，显示正确答案时使用第一个
+    Text(Vec<String>),
+}
+
+// 答案的依据
+pub enum Fact {
+    Output { section: String, text: String },
+    CompileFail(String),
+}
+
+pub struct Question {
+    pub id: String,
+    pub question: Text,
+    pub choices: Vec<Text>,
+    pub answer: Answer,
+    pub explain: Text,
+    pub facts: Vec<Fact>,
+}
+
+impl Question {
+    // 判断回答是否正确，选择题可以回答序号，也可以回答选项的文字
+    pub fn accepts(&self, reply: &str) -> bool {
+        let reply = normalize(reply);
+        match &self.answer {
+            Answer::Choice(n) => {
+                let choice = &self.choices[n - 1];
+                reply == n.to_string()
+                    || reply == normalize(&choice.zh)
+                    || choice
+                        .en
+                        .as_deref()
+                        .is_some_and(|en| reply == normalize(en))
+            }
+            Answer::Text(answers) => answers.contains(&reply),
+        }
+    }
+
+    // 用于显示的正确答案
+    pub fn correct(&self) -> String {
+        match &self.answer {
+            Answer::Choice(n) => format!("{}) {}", n, self.choices[n - 1].get()),
+            Answer::Text(answers) => answers[0].clone(),
+        }
+    }
+}
+
+pub struct Quiz {
+    // 所属的章节 id，即文件名
+    pub chapter: String,
+    pub questions: Vec<Question>,
+}
+
+/**
+ * 读取章节的测验，章节没有测验文件时返回 None
+ * 文件格式不对时返回错误，错误信息中包含文件名和行号
+ */
+pub fn load(chapter: &str) -> io::Result<Option<Quiz>> {
+    let Some((_, text)) = QUIZZES.iter().find(|(id, _)| *id == chapter) else {
+        return Ok(None);
+    };
+    let questions = parse(text).map_err(|(line, message)| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("quizzes/{}.txt:{}: {}", chapter, line, message),
+        )
+    })?;
+    Ok(Some(Quiz {
+        chapter: chapter.to_string(),
+        questions,
+    }))
+}
+
+// 题目读到一半时的字段，读完之后由 finish 检查并转换为 Question
+#[derive(Default)]
+struct Draft {
+    id: String,
+    line: usize,
+    question: Option<Text>,
+    choices: Vec<Text>,
+    choices_en: Vec<String>,
+    answer: Option<String>,
+    explain: Text,
+    facts: Vec<Fact>,
+}
+
+// 解析测验文件，出错时返回行号和原因
+fn parse(text: &str) -> Result<Vec<Question>, (usize, String)> {
+    let mut questions = Vec::new();
+    let mut draft: Option<Draft> = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let number = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(id) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if let Some(draft) = draft.take() {
+                questions.push(finish(draft)?);
+            }
+            draft = Some(Draft {
+                id: id.trim().to_string(),
+                line: number,
+                ..Draft::default()
+            });
+            continue;
+        }
+
+        let Some(current) = draft.as_mut() else {
+            return Err((number, String::from("expected `[question id]`")));
+        };
+        let Some((key, value)) = line.split_once('=') else {
+            return Err((number, String::from("expected `key = value`")));
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "question" => current.question = Some(Text::new(value)),
+            "question.en" => match current.question.as_mut() {
+                Some(question) => question.en = Some(value),
+                None => return Err((number, String::from("`question.en` before `question`"))),
+            },
+            "choice" => current.choices.push(Text::new(value)),
+            "choice.en" => current.choices_en.push(value),
+            "answer" => current.answer = Some(value),
+            "explain" => current.explain.zh = value,
+            "explain.en" => current.explain.en = Some(value),
+            "output" => match value.split_once('|') {
+                Some((section, text)) => current.facts.push(Fact::Output {
+                    section: section.trim().to_string(),
+                    text: text.trim().to_string(),
+                }),
+                None => return Err((number, String::from("expected `output = <fn> | <text>`"))),
+            },
+            "compile_fail" => current.facts.push(Fact::CompileFail(value)),
+            other => return Err((number, format!("unknown key `{}`", other))),
+        }
+    }
+    if let Some(draft) = draft {
+        questions.push(finish(draft)?);
+    }
+    Ok(questions)
+}
+
+fn finish(draft: Draft) -> Result<Question, (usize, String)> {
+    let line = draft.line;
+    let Some(question) = draft.question else {
+        return Err((line, format!("`{}` has no `question`", draft.id)));
+    };
+    let Some(answer) = draft.answer else {
+        return Err((line, format!("`{}` has no `answer`", draft.id)));
+    };
+    if draft.choices_en.len() > draft.choices.len() {
+        return Err((
+            line,
+            format!("`{}` has more `choice.en` than `choice`", draft.id),
+        ));
+    }
+
+    let mut choices = draft.choices;
+    for (choice, en) in choices.iter_mut().zip(draft.choices_en) {
+        choice.en = Some(en);
+    }
+    let answer = if choices.is_empty() {
+        let mut answers: Vec<String> = answer.split('|').map(str::trim).map(normalize).collect();
+        answers.retain(|a| !a.is_empty());
+        if answers.is_empty() {
+            return Err((line, format!("`{}` has an empty `answer`", draft.id)));
+        }
+        Answer::Text(answers)
+    } else {
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=choices.len()).contains(&n) => Answer::Choice(n),
+            _ => {
+                return Err((
+                    line,
+                    format!("`{}`: `answer` must be a choice number", draft.id),
+                ))
+            }
+        }
+    };
+
+    Ok(Question {
+        id: draft.id,
+        question,
+        choices,
+        answer,
+        explain: draft.explain,
+        facts: draft.facts,
+    })
+}
+
+/**
+ * 规范化回答，使写法上的小差别不影响对错:
+ * 全角字母、数字和标点转换为半角，英文字母转换为小写，
+ * 去掉反引号和引号，连续的空白合并为一个空格，去掉末尾的句号、问号和感叹号
+ */
+pub fn normalize(answer: &str) -> String {
+    let halfwidth: String = answer
+        .chars()
+        .map(|c| match c {
+            '\u{3000}' => ' ',
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+            _ => c,
+        })
+        .filter(|c| !matches!(c, '`' | '"' | '\'' | '“' | '”' | '‘' | '’'))
+        .collect::<String>()
+        .to_lowercase();
+    let words: Vec<&str> = halfwidth.split_whitespace().collect();
+    words
+        .join(" ")
+        .trim_end_matches(['.', '。', '!', '?'])
+        .to_string()
+}
+
+/**
+ * 对照课程的实际行为检查答案
+ *
+ * Confirmed:    所有依据都成立
+ * Unchecked:    题目没有写依据，无法自动检查
+ * Contradicted: 有依据不成立，记录原因，需要修改题目或答案
+ */
+pub enum Evidence {
+    Confirmed,
+    Unchecked,
+    Contradicted(String),
+}
+
+/**
+ * 检查测验中每道题的依据
+ *
+ * 课程函数的输出和快照一样以中文、默认输入和默认的日志规则运行，每个课程函数只运行一次
+ * 反例用本地 rustc 编译，见 compile_fail::check
+ */
+pub fn check(quiz: &Quiz) -> io::Result<Vec<Evidence>> {
+    let mut outputs: HashMap<&str, Option<String>> = HashMap::new();
+    let mut cases = None;
+
+    let mut results = Vec::new();
+    for question in &quiz.questions {
+        if question.facts.is_empty() {
+            results.push(Evidence::Unchecked);
+            continue;
+        }
+        let mut evidence = Evidence::Confirmed;
+        for fact in &question.facts {
+            let reason = match fact {
+                Fact::Output { section, text } => {
+                    let output = outputs
+                        .entry(section)
+                        .or_insert_with(|| run_section(section));
+                    match output {
+                        None => Some(tr!("quiz.unknown_section", section)),
+                        Some(output) if !output.contains(text.as_str()) => {
+                            Some(tr!("quiz.missing_output", section, text))
+                        }
+                        Some(_) => None,
+                    }
+                }
+                Fact::CompileFail(name) => {
+                    let cases = match &cases {
+                        Some(cases) => cases,
+                        None => cases.insert(compile_fail::cases()?),
+                    };
+                    match cases.iter().find(|case| &case.name == name) {
+                        None => Some(tr!("quiz.unknown_case", name)),
+                        Some(case) => match compile_fail::check(case)? {
+                            Check::Failed => None,
+                            _ => Some(tr!("quiz.case_broken", name, case.expect)),
+                        },
+                    }
+                }
+            };
+            if let Some(reason) = reason {
+                evidence = Evidence::Contradicted(reason);
+                break;
+            }
+        }
+        results.push(evidence);
+    }
+    Ok(results)
+}
+
+/**
+ * 运行一个课程函数并返回它的输出(包括 panic 信息)，课程函数不存在时返回 None
+ * 和快照一样以中文和默认的日志规则运行，只作用于这次运行，不改变全局的设置
+ */
+fn run_section(path: &str) -> Option<String> {
+    let (_, Some(section)) = lesson::lookup(path)? else {
+        return None;
+    };
+    let mut buf = Vec::new();
+    let outcome = i18n::with_locale(Locale::Zh, || {
+        let mut out = Output::new(&mut buf).with_filter(Filter::default());
+        runner::run_section(section, &mut out, &mut Input::Defaults)
+    });
+
+    let mut output = String::from_utf8_lossy(&buf).into_owned();
+    if let runner::Outcome::Panicked { message, .. } = outcome {
+        output.push_str(&message);
+    }
+    Some(output)
+}
+
+/**
+ * 逐题提问并从 input 读取回答，回答之后立即显示对错和说明，返回答对的题数
+ * 读不到输入时(如 stdin 为空)按没有回答处理，算作答错
+ */
+pub fn ask(quiz: &Quiz, input: &mut Input) -> io::Result<usize> {
+    let total = quiz.questions.len();
+    let mut correct = 0;
+    for (i, question) in quiz.questions.iter().enumerate() {
+        println!(
+            "{}",
+            tr!("quiz.question", i + 1, total, question.question.get())
+        );
+        for (n, choice) in question.choices.iter().enumerate() {
+            println!("    {}) {}", n + 1, choice.get());
+        }
+        println!("{}", tr!("quiz.prompt"));
+
+        let mut reply = String::new();
+        input.read_line(&mut reply, "")?;
+        if question.accepts(&reply) {
+            correct += 1;
+            println!("{}", tr!("quiz.correct"));
+        } else {
+            println!("{}", tr!("quiz.wrong", question.correct()));
+        }
+        let explain = question.explain.get();
+        if !explain.is_empty() {
+            println!("{}", tr!("quiz.explain", explain));
+        }
+        println!();
+    }
+    println!("{}", tr!("quiz.score", quiz.chapter, correct, total));
+    Ok(correct)
+}
+
+/**
+ * 依次做选中章节的测验并记录得分，没有测验的章节跳过
+ * 测验文件格式不对或者读取回答出错时停止，返回错误
+ */
+pub fn ask_all(lessons: &[&'static dyn Lesson], input: &mut Input) -> io::Result<()> {
+    for lesson in lessons {
+        let Some(quiz) = load(lesson.id())? else {
+            println!("{}", tr!("quiz.none", lesson.id()));
+            continue;
+        };
+        let correct = ask(&quiz, input)?;
+        progress::update(|p| p.record_quiz(&quiz.chapter, correct, quiz.questions.len()));
+    }
+    Ok(())
+}
+
+/**
+ * 对照课程的实际行为检查选中章节的测验答案并打印每道题的结果
+ * 返回是否全部通过，有答案不符或者测验无法读取、检查时为 false
+ */
+pub fn check_all(lessons: &[&'static dyn Lesson]) -> bool {
+    let mut failed = 0;
+    let mut errors = false;
+    for lesson in lessons {
+        let results = match load(lesson.id()) {
+            Ok(Some(quiz)) => check(&quiz).map(|results| (quiz, results)),
+            Ok(None) => continue,
+            Err(e) => Err(e),
+        };
+        let (quiz, results) = match results {
+            Ok(checked) => checked,
+            Err(e) => {
+                errors = true;
+                println!("{}", tr!("quiz.error", lesson.id(), e));
+                continue;
+            }
+        };
+        for (question, evidence) in quiz.questions.iter().zip(results) {
+            match evidence {
+                Evidence::Confirmed => println!("{}", tr!("quiz.ok", quiz.chapter, question.id)),
+                Evidence::Unchecked => {
+                    println!("{}", tr!("quiz.unchecked", quiz.chapter, question.id))
+                }
+                Evidence::Contradicted(reason) => {
+                    failed += 1;
+                    println!(
+                        "{}",
+                        tr!("quiz.contradicted", quiz.chapter, question.id, reason)
+                    );
+                }
+            }
+        }
+    }
+    if failed > 0 {
+        eprintln!("{}", tr!("quiz.total", failed));
+    }
+    failed == 0 && !errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# 注释\n\
+                          [f32_sum]\n\
+                          question = f32 中 `0.1 + 0.2 == 0.3` 成立吗?\n\
+                          question.en = Does `0.1 + 0.2 == 0.3` hold for f32?\n\
+                          choice = 成立\n\
+                          choice.en = it holds\n\
+                          choice = 不成立\n\
+                          choice.en = it does not hold\n\
+                          answer = 2\n\
+                          output = base_02::float_type | 0.3: 3e99999a\n\
+                          \n\
+                          [shadow]\n\
+                          question = 用什么关键字遮蔽变量?\n\
+                          answer = let | `let`\n\
+                          compile_fail = base_01_variables\n";
+
+    fn question(text: &str) -> Question {
+        parse(text).unwrap().remove(0)
+    }
+
+    #[test]
+    fn parse_reads_choice_and_text_questions() {
+        let questions = parse(SAMPLE).unwrap();
+        assert_eq!(questions.len(), 2);
+
+        let choice = &questions[0];
+        assert_eq!(choice.id, "f32_sum");
+        assert_eq!(choice.choices.len(), 2);
+        assert_eq!(choice.choices[1].en.as_deref(), Some("it does not hold"));
+        assert!(matches!(choice.answer, Answer::Choice(2)));
+        assert!(matches!(
+            &choice.facts[..],
+            [Fact::Output { section, text }] if section == "base_02::float_type" && text == "0.3: 3e99999a"
+        ));
+
+        let text = &questions[1];
+        assert!(text.choices.is_empty());
+        assert!(matches!(&text.answer, Answer::Text(answers) if answers == &["let", "let"]));
+        assert!(
+            matches!(&text.facts[..], [Fact::CompileFail(name)] if name == "base_01_variables")
+        );
+    }
+
+    #[test]
+    fn parse_reports_line_numbers() {
+        let error = |text: &str| parse(text).err().map(|(line, _)| line);
+        assert_eq!(error("question = 没有 id\n"), Some(1));
+        assert_eq!(error("[a]\nquestion = q\nanswer\n"), Some(3));
+        assert_eq!(
+            error("[a]\nquestion = q\nanswer = x\ncolour = red\n"),
+            Some(4)
+        );
+        assert_eq!(error("[a]\nquestion.en = q\n"), Some(2));
+        assert_eq!(
+            error("[a]\nquestion = q\noutput = base_01::variables\n"),
+            Some(3)
+        );
+        // 缺少字段的错误指向题目 id 所在的行
+        assert_eq!(
+            error("[a]\nquestion = q\nanswer = x\n\n[b]\nanswer = x\n"),
+            Some(5)
+        );
+        assert_eq!(
+            error("[a]\nquestion = q\nchoice = x\nanswer = 2\n"),
+            Some(1)
+        );
+        assert_eq!(error("[a]\nquestion = q\nanswer = |\n"), Some(1));
+        assert_eq!(
+            error("[a]\nquestion = q\nchoice.en = x\nanswer = 1\n"),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn normalize_ignores_spelling_differences() {
+        assert_eq!(normalize("  `Let`  Mut. "), "let mut");
+        assert_eq!(normalize("ＬＥＴ　ｍｕｔ！"), "let mut");
+        assert_eq!(normalize("“所有权”。"), "所有权");
+        assert_eq!(normalize("'a'?"), "a");
+        assert_eq!(normalize("i32"), "i32");
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn accepts_choice_number_or_text() {
+        let question = question(SAMPLE);
+        assert!(question.accepts("2"));
+        assert!(question.accepts(" ２ "));
+        assert!(question.accepts("不成立"));
+        assert!(question.accepts("It does NOT hold."));
+        assert!(!question.accepts("1"));
+        assert!(!question.accepts("成立"));
+        assert!(!question.accepts("3"));
+        assert!(!question.accepts(""));
+    }
+
+    #[test]
+    fn accepts_any_listed_text_answer() {
+        let question = question("[a]\nquestion = q\nanswer = 所有权 | ownership\n");
+        assert!(question.accepts("所有权"));
+        assert!(question.accepts("Ownership!"));
+        assert!(question.accepts("`ownership`"));
+        assert!(!question.accepts("borrowing"));
+        assert_eq!(question.correct(), "所有权");
+    }
+
+    #[test]
+    fn embedded_quizzes_parse() {
+        for (chapter, text) in QUIZZES {
+            let questions =
+                parse(text).unwrap_or_else(|(line, e)| panic!("{}:{}: {}", chapter, line, e));
+            assert!(!questions.is_empty(), "{}", chapter);
+        }
+    }
+}
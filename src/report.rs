@@ -0,0 +1,300 @@
+// 运行报告 Run reports in JSON and JUnit XML
+
+use std::fmt::Write;
+
+use crate::runner::{ChapterResult, Outcome};
+
+/**
+ * JSON 报告，格式如下
+ * {
+ *   "passed": 10,
+ *   "failed": 1,
+ *   "chapters": [
+ *     {
+ *       "id": "base_01",
+ *       "title": "变量 variable",
+ *       "duration_ms": 0.052,
+ *       "outcome": "passed",
+ *       "output": "...",
+ *       "panic": null
+ *     },
+ *     ...
+ *   ]
+ * }
+ * 章节 panic 时 outcome 为 "panicked"，panic 为 {"message": "...", "location": "..."}
+ */
+pub fn to_json(results: &[ChapterResult]) -> String {
+    let failed = results.iter().filter(|r| !r.outcome.is_passed()).count();
+
+    let mut json = String::new();
+    json.push_str("{\n");
+    let _ = writeln!(json, "  \"passed\": {},", results.len() - failed);
+    let _ = writeln!(json, "  \"failed\": {},", failed);
+    json.push_str("  \"chapters\": [");
+    for (i, result) in results.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str("\n    {\n");
+        let _ = writeln!(json, "      \"id\": {},", json_str(result.lesson.id()));
+        let _ = writeln!(
+            json,
+            "      \"title\": {},",
+            json_str(result.lesson.title())
+        );
+        let _ = writeln!(
+            json,
+            "      \"duration_ms\": {:.3},",
+            result.usage.duration.as_secs_f64() * 1000.0
+        );
+        let outcome = if result.outcome.is_passed() {
+            "passed"
+        } else {
+            "panicked"
+        };
+        let _ = writeln!(json, "      \"outcome\": {},", json_str(outcome));
+        let output = result.output.as_deref().unwrap_or_default();
+        let _ = writeln!(json, "      \"output\": {},", json_str(output));
+        match &result.outcome {
+            Outcome::Passed => json.push_str("      \"panic\": null\n"),
+            Outcome::Panicked { message, location } => {
+                let location = match location {
+                    Some(location) => json_str(location),
+                    None => String::from("null"),
+                };
+                let _ = writeln!(
+                    json,
+                    "      \"panic\": {{\"message\": {}, \"location\": {}}}",
+                    json_str(message),
+                    location
+                );
+            }
+        }
+        json.push_str("    }");
+    }
+    if !results.is_empty() {
+        json.push_str("\n  ");
+    }
+    json.push_str("]\n}\n");
+    json
+}
+
+fn json_str(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/**
+ * JUnit XML 报告，每个章节是一个 testcase，panic 的章节带有 failure
+ * 章节的输出放在 system-out 中
+ */
+pub fn to_junit(results: &[ChapterResult]) -> String {
+    let failed = results.iter().filter(|r| !r.outcome.is_passed()).count();
+    let total: f64 = results.iter().map(|r| r.usage.duration.as_secs_f64()).sum();
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites tests=\"{}\" failures=\"{}\" errors=\"0\" time=\"{:.6}\">",
+        results.len(),
+        failed,
+        total
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"rust-learn\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\" time=\"{:.6}\">",
+        results.len(),
+        failed,
+        total
+    );
+    for result in results {
+        let _ = writeln!(
+            xml,
+            "    <testcase classname=\"rust-learn\" name=\"{}\" time=\"{:.6}\">",
+            xml_escape(&format!("{} {}", result.lesson.id(), result.lesson.title())),
+            result.usage.duration.as_secs_f64()
+        );
+        if let Outcome::Panicked { message, location } = &result.outcome {
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\" type=\"panic\">{}</failure>",
+                xml_escape(message),
+                xml_escape(location.as_deref().unwrap_or_default())
+            );
+        }
+        if let Some(output) = &result.output {
+            let _ = writeln!(xml, "      <system-out>{}</system-out>", xml_escape(output));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+// XML 中不允许出现除 \t \n \r 以外的控制字符，直接丢弃，HTML 导出也使用它转义
+pub fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        lesson::{Lesson, Section},
+        stats::Usage,
+    };
+
+    struct Fake(&'static str, &'static str);
+
+    impl Lesson for Fake {
+        fn id(&self) -> &'static str {
+            self.0
+        }
+
+        fn title(&self) -> &'static str {
+            self.1
+        }
+
+        fn topics(&self) -> &'static [&'static str] {
+            &[]
+        }
+
+        fn sections(&self) -> &'static [Section] {
+            &[]
+        }
+    }
+
+    static PASSED: Fake = Fake("base_01", "变量 variable");
+    static PANICKED: Fake = Fake("base_04", "\"quoted\" <&>");
+
+    fn results() -> Vec<ChapterResult> {
+        let usage = Usage {
+            duration: Duration::from_micros(1500),
+            ..Usage::default()
+        };
+        vec![
+            ChapterResult {
+                lesson: &PASSED,
+                outcome: Outcome::Passed,
+                usage,
+                sections: Vec::new(),
+                output: Some(String::from("x = 5\n")),
+            },
+            ChapterResult {
+                lesson: &PANICKED,
+                outcome: Outcome::Panicked {
+                    message: String::from("index out of bounds: the len is 5 but the index is 9"),
+                    location: Some(String::from("src/base_04.rs:230:19")),
+                },
+                usage,
+                sections: Vec::new(),
+                output: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn json_str_escapes_quotes_and_control_characters() {
+        assert_eq!(json_str("plain 中文"), "\"plain 中文\"");
+        assert_eq!(json_str("say \"hi\" \\ ok"), "\"say \\\"hi\\\" \\\\ ok\"");
+        assert_eq!(json_str("a\nb\tc\rd"), "\"a\\nb\\tc\\rd\"");
+        assert_eq!(json_str("\x1b[0m\x00"), "\"\\u001b[0m\\u0000\"");
+    }
+
+    #[test]
+    fn xml_escape_escapes_markup_and_drops_control_characters() {
+        assert_eq!(
+            xml_escape("<a href=\"x\">&'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&apos;&lt;/a&gt;"
+        );
+        assert_eq!(xml_escape("a\tb\nc\rd"), "a\tb\nc\rd");
+        assert_eq!(xml_escape("\x1b[1mbold\x1b[0m\x00"), "[1mbold[0m");
+    }
+
+    #[test]
+    fn json_report_shape() {
+        let json = to_json(&results());
+        let expected = r#"{
+  "passed": 1,
+  "failed": 1,
+  "chapters": [
+    {
+      "id": "base_01",
+      "title": "变量 variable",
+      "duration_ms": 1.500,
+      "outcome": "passed",
+      "output": "x = 5\n",
+      "panic": null
+    },
+    {
+      "id": "base_04",
+      "title": "\"quoted\" <&>",
+      "duration_ms": 1.500,
+      "outcome": "panicked",
+      "output": "",
+      "panic": {"message": "index out of bounds: the len is 5 but the index is 9", "location": "src/base_04.rs:230:19"}
+    }
+  ]
+}
+"#;
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn json_report_without_chapters() {
+        assert_eq!(
+            to_json(&[]),
+            "{\n  \"passed\": 0,\n  \"failed\": 0,\n  \"chapters\": []\n}\n"
+        );
+    }
+
+    #[test]
+    fn junit_report_shape() {
+        let xml = to_junit(&results());
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="2" failures="1" errors="0" time="0.003000">
+  <testsuite name="rust-learn" tests="2" failures="1" errors="0" skipped="0" time="0.003000">
+    <testcase classname="rust-learn" name="base_01 变量 variable" time="0.001500">
+      <system-out>x = 5
+</system-out>
+    </testcase>
+    <testcase classname="rust-learn" name="base_04 &quot;quoted&quot; &lt;&amp;&gt;" time="0.001500">
+      <failure message="index out of bounds: the len is 5 but the index is 9" type="panic">src/base_04.rs:230:19</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#;
+        assert_eq!(xml, expected);
+    }
+}
@@ -0,0 +1,533 @@
+// 隔离运行章节 Run chapters in isolation
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fs::{self, File},
+    io,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Once,
+    },
+    thread,
+};
+
+use crate::{
+    input::Input,
+    lesson::{Lesson, Section},
+    log::{self, Level},
+    output::Output,
+    progress, quiz, report, source,
+    stats::{self, Usage},
+};
+
+/**
+ * 章节运行结果
+ *
+ * Panicked 记录 panic 的信息和位置，如 src/base_04.rs:230:19
+ */
+pub enum Outcome {
+    Passed,
+    Panicked {
+        message: String,
+        location: Option<String>,
+    },
+}
+
+impl Outcome {
+    pub fn is_passed(&self) -> bool {
+        matches!(self, Outcome::Passed)
+    }
+}
+
+/**
+ * usage:    整个章节的耗时和堆分配
+ * sections: 每个课程函数的耗时和堆分配，panic 的课程函数及其后的函数不会出现
+ * output:   只有通过 run_captured 运行时才有，是章节的全部输出(包括 dbg 输出)
+ */
+pub struct ChapterResult {
+    pub lesson: &'static dyn Lesson,
+    pub outcome: Outcome,
+    pub usage: Usage,
+    pub sections: Vec<(&'static str, Usage)>,
+    pub output: Option<String>,
+}
+
+thread_local! {
+    // 当前线程是否正在隔离运行章节
+    static ISOLATED: Cell<bool> = const { Cell::new(false) };
+    // 隔离运行期间最近一次 panic 的信息和位置
+    static LAST_PANIC: RefCell<Option<(String, Option<String>)>> = const { RefCell::new(None) };
+}
+
+/**
+ * 安装 panic hook，只需要一次
+ *
+ * 隔离运行期间 panic 不再直接打印到 stderr，而是记录下来由调用方决定如何展示
+ * 其他情况仍然交给默认的 hook 处理
+ */
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !ISOLATED.with(Cell::get) {
+                default_hook(info);
+                return;
+            }
+            // 记录 panic 信息是运行器的开销，不计入课程的统计
+            stats::untracked(|| {
+                let payload = info.payload();
+                let message = if let Some(s) = payload.downcast_ref::<&str>() {
+                    s.to_string()
+                } else if let Some(s) = payload.downcast_ref::<String>() {
+                    s.clone()
+                } else {
+                    String::from("Box<dyn Any>")
+                };
+                let location = info
+                    .location()
+                    .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+                LAST_PANIC.with(|p| *p.borrow_mut() = Some((message, location)));
+            });
+        }));
+    });
+}
+
+/**
+ * 运行一个章节，章节中的 panic 会被捕获并记录，不会中断后续章节
+ * with_source 为 true 时，每个课程函数运行之前先打印它的注释和代码
+ */
+pub fn run(
+    lesson: &'static dyn Lesson,
+    out: &mut Output,
+    input: &mut Input,
+    with_source: bool,
+) -> ChapterResult {
+    // 预先分配好，章节的统计只包含课程函数自己的分配，打印代码和日志也不计入
+    let mut sections = Vec::with_capacity(lesson.sections().len());
+    let mut each = |section: &Section, out: &mut Output, input: &mut Input| {
+        stats::untracked(|| {
+            if with_source {
+                if let Some(snippet) = source::find(lesson.id(), section.name) {
+                    source::print(out, lesson.id(), snippet);
+                    outln!(out, "---------- {} ----------", tr!("source.output"));
+                }
+            }
+            let start = tr!("runner.section_start", lesson.id(), section.name);
+            out.log(Level::Trace, lesson.id(), format_args!("{}\n", start));
+        });
+        let ((), usage) = stats::measure(|| (section.run)(out, input));
+        stats::untracked(|| {
+            let end = tr!(
+                "runner.section_end",
+                lesson.id(),
+                section.name,
+                format!("{:.3?}", usage.duration)
+            );
+            out.log(Level::Trace, lesson.id(), format_args!("{}\n", end));
+        });
+        sections.push((section.name, usage));
+    };
+
+    let (outcome, usage) = stats::measure(|| isolated(|| lesson.run_with(out, input, &mut each)));
+
+    ChapterResult {
+        lesson,
+        outcome,
+        usage,
+        sections,
+        output: None,
+    }
+}
+
+/**
+ * 单独运行一个课程函数，同样会捕获其中的 panic
+ */
+pub fn run_section(section: &Section, out: &mut Output, input: &mut Input) -> Outcome {
+    isolated(|| (section.run)(out, input))
+}
+
+// 运行 f 并捕获其中的 panic
+fn isolated(f: impl FnOnce()) -> Outcome {
+    stats::untracked(install_hook);
+
+    ISOLATED.with(|i| i.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    ISOLATED.with(|i| i.set(false));
+
+    match result {
+        Ok(()) => Outcome::Passed,
+        Err(_) => {
+            let (message, location) = LAST_PANIC
+                .with(|p| p.borrow_mut().take())
+                .unwrap_or_else(|| (String::from("unknown panic"), None));
+            Outcome::Panicked { message, location }
+        }
+    }
+}
+
+/**
+ * 与 run 相同，但是把章节的输出捕获到 ChapterResult::output 中
+ */
+pub fn run_captured(
+    lesson: &'static dyn Lesson,
+    input: &mut Input,
+    with_source: bool,
+) -> ChapterResult {
+    let mut buf = Vec::new();
+    let mut result = run(lesson, &mut Output::new(&mut buf), input, with_source);
+    result.output = Some(String::from_utf8_lossy(&buf).into_owned());
+    result
+}
+
+/**
+ * run 命令的选项
+ *
+ * output:      章节输出写到这个文件中，None 时写到终端
+ * json, junit: 运行结束后写出的报告
+ * stats:       运行结束后打印耗时和堆分配
+ * with_source: 每个课程函数运行之前先打印它的注释和代码
+ * jobs:        并行运行章节的线程数，见 Pool
+ * quiz:        运行结束后做这些章节的测验
+ */
+pub struct RunOptions {
+    pub lessons: Vec<&'static dyn Lesson>,
+    pub output: Option<PathBuf>,
+    pub input: Input,
+    pub json: Option<PathBuf>,
+    pub junit: Option<PathBuf>,
+    pub stats: bool,
+    pub with_source: bool,
+    pub jobs: usize,
+    pub quiz: bool,
+}
+
+/**
+ * 按课程顺序依次运行选中的章节，记录进度，最后打印汇总表，返回失败的章节数
+ * 无法创建输出文件或者写出报告时返回错误，错误信息中包含文件名
+ */
+pub fn run_all(options: RunOptions) -> io::Result<usize> {
+    let mut out = match &options.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Output::new(file),
+            Err(e) => {
+                let message = tr!("cli.cannot_create", path.display(), e);
+                return Err(io::Error::new(e.kind(), message));
+            }
+        },
+        None => Output::console(),
+    };
+    // 需要写报告时捕获每个章节的输出，运行结束后再原样转发
+    let capture = options.json.is_some() || options.junit.is_some();
+
+    let mut input = options.input;
+    let mut results = Vec::new();
+    let mut pool = Pool::start(&options.lessons, options.jobs, options.with_source);
+    for (i, lesson) in options.lessons.into_iter().enumerate() {
+        let result = if let Some(result) = pool.take(i) {
+            out!(out, "{}", result.output.as_deref().unwrap_or_default());
+            result
+        } else if capture {
+            let result = run_captured(lesson, &mut input, options.with_source);
+            out!(out, "{}", result.output.as_deref().unwrap_or_default());
+            result
+        } else {
+            run(lesson, &mut out, &mut input, options.with_source)
+        };
+        if let Outcome::Panicked { message, location } = &result.outcome {
+            let location = location.as_deref().unwrap_or("<unknown>");
+            log::error(
+                lesson.id(),
+                &tr!("runner.panicked", lesson.id(), location, message),
+            );
+        }
+        results.push(result);
+    }
+    progress::update(|p| {
+        for result in &results {
+            p.record_chapter(result.lesson.id(), result.outcome.is_passed());
+        }
+    });
+    let failed = print_summary(&results);
+    if options.stats {
+        print_stats(&results);
+    }
+    if options.quiz {
+        let lessons: Vec<&'static dyn Lesson> = results.iter().map(|r| r.lesson).collect();
+        if let Err(e) = quiz::ask_all(&lessons, &mut input) {
+            eprintln!("{}", tr!("cli.error", e));
+        }
+    }
+
+    let mut reports = Vec::new();
+    if let Some(path) = options.json {
+        reports.push((path, report::to_json(&results)));
+    }
+    if let Some(path) = options.junit {
+        reports.push((path, report::to_junit(&results)));
+    }
+    for (path, text) in reports {
+        if let Err(e) = fs::write(&path, text) {
+            let message = tr!("cli.cannot_write", path.display(), e);
+            return Err(io::Error::new(e.kind(), message));
+        }
+    }
+    Ok(failed)
+}
+
+/**
+ * 并行运行章节的线程池
+ *
+ * 不是 serial 的章节交给 jobs 个后台线程运行，输出捕获到 ChapterResult::output 中，
+ * 这些章节不读取输入，需要输入时使用默认答案
+ * 调用方按章节顺序用 take 取结果，无论哪个章节先完成，输出的顺序都和串行运行时相同
+ * serial 的章节不进入线程池，take 返回 None，由调用方在当前线程中运行
+ */
+pub struct Pool {
+    queued: Vec<usize>,
+    results: mpsc::Receiver<(usize, ChapterResult)>,
+    finished: HashMap<usize, ChapterResult>,
+}
+
+impl Pool {
+    // jobs 不大于 1 时不启动任何线程，所有章节都由调用方串行运行
+    pub fn start(lessons: &[&'static dyn Lesson], jobs: usize, with_source: bool) -> Pool {
+        let queue: Vec<(usize, &'static dyn Lesson)> = if jobs > 1 {
+            lessons
+                .iter()
+                .copied()
+                .enumerate()
+                .filter(|(_, lesson)| !lesson.serial())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let queued = queue.iter().map(|(i, _)| *i).collect();
+
+        let (sender, results) = mpsc::channel();
+        let queue = Arc::new(queue);
+        let next = Arc::new(AtomicUsize::new(0));
+        for _ in 0..jobs.min(queue.len()) {
+            let sender = sender.clone();
+            let queue = Arc::clone(&queue);
+            let next = Arc::clone(&next);
+            thread::spawn(move || {
+                while let Some(&(i, lesson)) = queue.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let result = run_captured(lesson, &mut Input::Defaults, with_source);
+                    if sender.send((i, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        Pool {
+            queued,
+            results,
+            finished: HashMap::new(),
+        }
+    }
+
+    // 取第 i 个章节的结果，还没有运行完时等待，这个章节不在线程池中时返回 None
+    pub fn take(&mut self, i: usize) -> Option<ChapterResult> {
+        if !self.queued.contains(&i) {
+            return None;
+        }
+        loop {
+            if let Some(result) = self.finished.remove(&i) {
+                return Some(result);
+            }
+            // 章节中的 panic 都已经被捕获，线程不会提前退出
+            let (j, result) = self.results.recv().expect("worker thread exited");
+            self.finished.insert(j, result);
+        }
+    }
+}
+
+/**
+ * 打印汇总表，返回失败的章节数
+ */
+pub fn print_summary(results: &[ChapterResult]) -> usize {
+    println!("========== {} ==========", tr!("runner.summary"));
+    for result in results {
+        let status = match &result.outcome {
+            Outcome::Passed => tr!("runner.ok"),
+            Outcome::Panicked { message, location } => match location {
+                Some(location) => format!("{}  {} ({})", tr!("runner.failed"), message, location),
+                None => format!("{}  {}", tr!("runner.failed"), message),
+            },
+        };
+        println!(
+            "{}  {:>10.3?}  {}",
+            result.lesson.id(),
+            result.usage.duration,
+            status
+        );
+    }
+
+    let failed = results.iter().filter(|r| !r.outcome.is_passed()).count();
+    let passed = results.len() - failed;
+    println!("{}", tr!("runner.totals", results.len(), passed, failed));
+    failed
+}
+
+/**
+ * 打印每个章节以及每个课程函数的耗时、分配次数和分配的字节数
+ */
+pub fn print_stats(results: &[ChapterResult]) {
+    println!("========== {} ==========", tr!("runner.stats"));
+    println!(
+        "{:<36} {:>12} {:>8} {:>10}",
+        tr!("runner.stats_name"),
+        tr!("runner.stats_time"),
+        tr!("runner.stats_allocs"),
+        tr!("runner.stats_bytes")
+    );
+    for result in results {
+        print_usage(result.lesson.id(), &result.usage);
+        for (name, usage) in &result.sections {
+            print_usage(&format!("  {}", name), usage);
+        }
+    }
+}
+
+fn print_usage(name: &str, usage: &Usage) {
+    println!(
+        "{:<36} {:>12.1?} {:>8} {:>10}",
+        name, usage.duration, usage.allocs, usage.bytes
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{lesson, snapshot};
+
+    struct Fake {
+        id: &'static str,
+        sections: &'static [Section],
+        serial: bool,
+    }
+
+    impl Lesson for Fake {
+        fn id(&self) -> &'static str {
+            self.id
+        }
+
+        fn title(&self) -> &'static str {
+            self.id
+        }
+
+        fn topics(&self) -> &'static [&'static str] {
+            &[]
+        }
+
+        fn sections(&self) -> &'static [Section] {
+            self.sections
+        }
+
+        fn serial(&self) -> bool {
+            self.serial
+        }
+    }
+
+    // 第一个章节最慢，线程池中它最后完成
+    fn slow(out: &mut Output, _: &mut Input) {
+        thread::sleep(Duration::from_millis(100));
+        outln!(out, "slow");
+    }
+
+    fn fast(out: &mut Output, _: &mut Input) {
+        outln!(out, "fast");
+    }
+
+    fn failing(_: &mut Output, _: &mut Input) {
+        panic!("boom");
+    }
+
+    static SLOW: Fake = Fake {
+        id: "slow",
+        sections: &[Section::new("slow", slow)],
+        serial: false,
+    };
+    static FAST: Fake = Fake {
+        id: "fast",
+        sections: &[Section::new("fast", fast)],
+        serial: false,
+    };
+    static FAILING: Fake = Fake {
+        id: "failing",
+        sections: &[Section::new("fast", fast), Section::new("failing", failing)],
+        serial: false,
+    };
+    static SERIAL: Fake = Fake {
+        id: "serial",
+        sections: &[Section::new("fast", fast)],
+        serial: true,
+    };
+
+    #[test]
+    fn pool_returns_results_in_chapter_order() {
+        let lessons: [&'static dyn Lesson; 5] = [&SLOW, &FAST, &SERIAL, &FAILING, &FAST];
+        let mut pool = Pool::start(&lessons, 4, false);
+
+        let mut taken = Vec::new();
+        for (i, lesson) in lessons.iter().enumerate() {
+            match pool.take(i) {
+                Some(result) => {
+                    assert_eq!(result.lesson.id(), lesson.id());
+                    let outcome = match result.outcome {
+                        Outcome::Passed => "passed",
+                        Outcome::Panicked { .. } => "panicked",
+                    };
+                    taken.push((result.output.unwrap_or_default(), outcome));
+                }
+                // serial 的章节由调用方运行
+                None => taken.push((String::from("<serial>"), "")),
+            }
+        }
+        assert_eq!(
+            taken,
+            [
+                (String::from("slow\n"), "passed"),
+                (String::from("fast\n"), "passed"),
+                (String::from("<serial>"), ""),
+                (String::from("fast\n"), "panicked"),
+                (String::from("fast\n"), "passed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn pool_without_jobs_runs_nothing() {
+        let lessons: [&'static dyn Lesson; 2] = [&SLOW, &FAST];
+        let mut pool = Pool::start(&lessons, 1, false);
+        assert!(pool.take(0).is_none());
+        assert!(pool.take(1).is_none());
+    }
+
+    // 和 run --jobs 相同: 并行运行的章节的输出与串行运行时一致
+    #[test]
+    fn parallel_output_matches_serial() {
+        let lessons: Vec<&'static dyn Lesson> = lesson::registry()
+            .iter()
+            .copied()
+            .filter(|lesson| !lesson.serial())
+            .collect();
+        let mut pool = Pool::start(&lessons, 4, false);
+        for (i, lesson) in lessons.iter().enumerate() {
+            let parallel = pool.take(i).unwrap();
+            let serial = run_captured(*lesson, &mut Input::Defaults, false);
+            assert_eq!(parallel.lesson.id(), lesson.id());
+            let normalize = |result: ChapterResult| {
+                snapshot::normalize(&result.output.unwrap(), lesson.unordered())
+            };
+            assert_eq!(normalize(parallel), normalize(serial), "{}", lesson.id());
+        }
+    }
+}
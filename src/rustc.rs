@@ -0,0 +1,70 @@
+// 调用本地 rustc Invoke the local rustc
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/**
+ * 本地 rustc 的命令，使用与课程相同的 edition
+ * 可以通过环境变量 RUSTC 指定 rustc 的路径
+ */
+pub fn command() -> Command {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+    let mut command = Command::new(rustc);
+    command.args(["--edition", "2021"]);
+    command
+}
+
+// 编译产物放在临时目录下，不弄脏仓库
+pub fn out_dir(name: &str) -> PathBuf {
+    env::temp_dir().join(name)
+}
+
+// 目录下的全部 .rs 文件，按文件名排序
+pub fn sources(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|p| p.extension().is_some_and(|ext| ext == "rs"));
+    paths.sort();
+    Ok(paths)
+}
+
+// 文件名去掉 .rs，如 base_01_variables
+pub fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/**
+ * 读取文件开头注释中的字段，如 // lesson: base_01::variables 中 lesson: 的值
+ * 只看文件开头连续的注释行
+ */
+pub fn header(source: &str, key: &str) -> Option<String> {
+    source
+        .lines()
+        .take_while(|line| line.starts_with("//"))
+        .find_map(|line| line.trim_start_matches('/').trim().strip_prefix(key))
+        .map(|value| value.trim().to_string())
+}
+
+// 从 rustc 的输出中取出 error[E0384] 这样的错误码，去重并保持出现的顺序
+pub fn error_codes(stderr: &str) -> Vec<String> {
+    let mut codes: Vec<String> = Vec::new();
+    for code in stderr.lines().filter_map(error_code) {
+        if !codes.iter().any(|c| c == code) {
+            codes.push(code.to_string());
+        }
+    }
+    codes
+}
+
+// 一行 rustc 输出中的错误码，如 error[E0384]: cannot assign twice 中的 E0384
+pub fn error_code(line: &str) -> Option<&str> {
+    line.strip_prefix("error[")
+        .and_then(|rest| rest.split_once(']'))
+        .map(|(code, _)| code)
+}
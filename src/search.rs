@@ -0,0 +1,472 @@
+// 搜索 Keyword search across chapters
+
+use std::sync::OnceLock;
+
+use crate::{
+    i18n::{self, Locale},
+    lesson,
+    lexer::{self, Kind, Token},
+    source::{self, Snippet},
+};
+
+// 词出现在不同位置时的权重
+const NAME: u32 = 10;
+const TITLE: u32 = 8;
+const DOC: u32 = 5;
+const COMMENT: u32 = 3;
+const TEXT: u32 = 2;
+const IDENT: u32 = 1;
+
+// 整个搜索词原样出现在某一行中时额外加分
+const PHRASE: u32 = 20;
+
+// 最多显示的结果数
+const LIMIT: usize = 10;
+
+/**
+ * 中英文对照的术语，搜索其中一种语言时也会搜索另一种，得分减半
+ * 课程的注释基本是中文的，这样用英文也能找到，如 shadowing 能找到“变量遮蔽”
+ */
+const GLOSSARY: &[(&str, &str)] = &[
+    ("variable", "变量"),
+    ("constant", "常量"),
+    ("shadowing", "遮蔽"),
+    ("mutable", "可变"),
+    ("ownership", "所有权"),
+    ("borrow", "借用"),
+    ("reference", "引用"),
+    ("lifetime", "生命周期"),
+    ("scope", "作用域"),
+    ("move", "移动"),
+    ("copy", "拷贝"),
+    ("slice", "切片"),
+    ("string", "字符串"),
+    ("char", "字符"),
+    ("float", "浮点"),
+    ("struct", "结构体"),
+    ("enum", "枚举"),
+    ("tuple", "元组"),
+    ("array", "数组"),
+    ("destructure", "解构"),
+    ("loop", "循环"),
+    ("match", "匹配"),
+    ("pattern", "模式"),
+    ("method", "方法"),
+    ("function", "函数"),
+    ("expression", "表达式"),
+    ("statement", "语句"),
+    ("generic", "泛型"),
+    ("trait", "特征"),
+    ("collection", "集合"),
+    ("iterator", "迭代"),
+    ("macro", "宏"),
+    ("error", "错误"),
+];
+
+/**
+ * 一条搜索结果
+ *
+ * function: 所在的函数，为 None 时匹配的是章节的标题或主题
+ * line:     匹配最好的一行在 src/{chapter}.rs 中的行号
+ * text:     这一行的源代码
+ * key:      这一行用 tr! 输出的消息，显示时换成当前语言的文字
+ */
+pub struct Hit {
+    pub chapter: &'static str,
+    pub function: Option<&'static str>,
+    pub line: usize,
+    pub text: &'static str,
+    pub key: Option<&'static str>,
+}
+
+// 索引中的一个函数或者一个章节的标题和主题
+struct Document {
+    chapter: &'static str,
+    function: Option<&'static str>,
+    entries: Vec<Entry>,
+}
+
+// 一行中同一种位置(注释、字符串...)的所有词
+struct Entry {
+    line: usize,
+    weight: u32,
+    text: &'static str,
+    key: Option<&'static str>,
+    terms: Vec<String>,
+}
+
+/**
+ * 搜索函数名、注释和字符串，按相关程度排序
+ *
+ * 搜索词按和索引相同的方式切分，中文切成相邻两个字的词，英文不区分大小写，
+ * 标识符还会按 _ 和大小写拆开(variable_shadowing 拆出 variable 和 shadowing)
+ * 只保留匹配了最多搜索词的结果，再按得分排序，得分相同时按课程顺序
+ */
+pub fn search(query: &str) -> Vec<Hit> {
+    let groups = groups(query);
+    let phrase = query.trim().to_lowercase();
+    if groups.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scored = Vec::new();
+    for document in index() {
+        // 每个搜索词在整个函数中的最高得分，以及匹配最好的一行
+        let mut best = vec![0; groups.len()];
+        let mut line: Option<(usize, u32, &Entry)> = None;
+        for entry in &document.entries {
+            let mut matched = 0;
+            let mut score = 0;
+            for (i, group) in groups.iter().enumerate() {
+                let weight = weight(group, entry);
+                if weight > 0 {
+                    matched += 1;
+                    score += weight;
+                    best[i] = best[i].max(weight);
+                }
+            }
+            if entry.text.to_lowercase().contains(&phrase) {
+                score += PHRASE;
+            }
+            if score > 0 && line.is_none_or(|(m, s, _)| (matched, score) > (m, s)) {
+                line = Some((matched, score, entry));
+            }
+        }
+
+        let Some((_, line_score, entry)) = line else {
+            continue;
+        };
+        let coverage = best.iter().filter(|w| **w > 0).count();
+        let score = best.iter().sum::<u32>() + line_score;
+        let hit = Hit {
+            chapter: document.chapter,
+            function: document.function,
+            line: entry.line,
+            text: entry.text,
+            key: entry.key,
+        };
+        scored.push((coverage, score, hit));
+    }
+
+    let most = scored.iter().map(|(coverage, ..)| *coverage).max();
+    scored.retain(|(coverage, ..)| Some(*coverage) == most);
+    scored.sort_by_key(|(_, score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(.., hit)| hit).collect()
+}
+
+/**
+ * 打印搜索结果，最多 LIMIT 条
+ *
+ *  1. base_01::variable_shadowing  src/base_01.rs:63
+ *     // 变量遮蔽 允许神明同名变量，后声明覆盖前面的
+ */
+pub fn print(query: &str, hits: &[Hit]) {
+    if hits.is_empty() {
+        println!("{}", tr!("search.no_match", query));
+        return;
+    }
+    for (i, hit) in hits.iter().take(LIMIT).enumerate() {
+        let name = match hit.function {
+            Some(function) => format!("{}::{}", hit.chapter, function),
+            None => hit.chapter.to_string(),
+        };
+        println!(
+            "{:>2}. {}  src/{}.rs:{}",
+            i + 1,
+            name,
+            hit.chapter,
+            hit.line
+        );
+        println!("    {}", hit.text);
+        if let Some(key) = hit.key {
+            println!("    = {}", tr!(key).replace('\n', " "));
+        }
+    }
+    if hits.len() > LIMIT {
+        println!("{}", tr!("search.more", hits.len() - LIMIT));
+    }
+}
+
+// 搜索词在这一行中的得分，没有匹配时为 0
+fn weight(group: &[(String, bool)], entry: &Entry) -> u32 {
+    let mut best = 0;
+    for (term, translated) in group {
+        for t in &entry.terms {
+            // 英文可以只写开头，如 borrow 能找到 borrowing，得分减半
+            let weight = if t == term {
+                entry.weight * 2
+            } else if term.len() >= 3 && term.is_ascii() && t.starts_with(term.as_str()) {
+                entry.weight
+            } else {
+                continue;
+            };
+            best = best.max(if *translated { weight / 2 } else { weight });
+        }
+    }
+    best
+}
+
+/**
+ * 把搜索词切分成词，每个词连同它在术语表中的翻译作为一组，组内任意一个匹配即可
+ * 翻译出来的词标记为 true
+ */
+fn groups(query: &str) -> Vec<Vec<(String, bool)>> {
+    let mut groups: Vec<Vec<(String, bool)>> = Vec::new();
+    for term in terms(query, false) {
+        if groups.iter().any(|group| group[0].0 == term) {
+            continue;
+        }
+        let mut group = vec![(term.clone(), false)];
+        for (en, zh) in GLOSSARY {
+            if term == *en {
+                group.extend(terms(zh, false).into_iter().map(|t| (t, true)));
+            } else if terms(zh, false).contains(&term) {
+                group.push((en.to_string(), true));
+            }
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+fn index() -> &'static [Document] {
+    static INDEX: OnceLock<Vec<Document>> = OnceLock::new();
+    INDEX.get_or_init(build)
+}
+
+/**
+ * 索引所有章节的标题、主题以及每个函数的名字、注释、字符串和标识符
+ * 用 tr! 输出的字符串按消息的 key 取出中英文两种文字来索引
+ */
+fn build() -> Vec<Document> {
+    let mut documents = Vec::new();
+    for lesson in lesson::registry() {
+        let id = lesson.id();
+        let mut words = terms(lesson.title(), true);
+        for topic in lesson.topics() {
+            words.extend(terms(topic, true));
+        }
+        let title = Entry {
+            line: source::find(id, "title").map_or(1, |s| s.line),
+            weight: TITLE,
+            text: lesson.title(),
+            key: None,
+            terms: words,
+        };
+        documents.push(Document {
+            chapter: id,
+            function: None,
+            entries: vec![title],
+        });
+
+        for snippet in source::snippets(id) {
+            if source::LESSON_METHODS.contains(&snippet.name) {
+                continue;
+            }
+            documents.push(Document {
+                chapter: id,
+                function: Some(snippet.name),
+                entries: entries(snippet),
+            });
+        }
+    }
+    documents
+}
+
+fn entries(snippet: &'static Snippet) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut named = false;
+    // 注释紧挨在代码前面，算出它第一行的行号
+    let doc_line = snippet.line - snippet.doc.lines().count();
+    for (text, first_line, comment) in [
+        (snippet.doc, doc_line, DOC),
+        (snippet.code, snippet.line, COMMENT),
+    ] {
+        let lines: Vec<&'static str> = text.split('\n').collect();
+        let tokens = lexer::tokenize(text);
+        for (i, token) in tokens.iter().enumerate() {
+            let (weight, key) = match token.kind {
+                Kind::LineComment | Kind::BlockComment => (comment, None),
+                Kind::Str => (TEXT, tr_key(&tokens[..i], token)),
+                // 第一次出现的函数名就是函数定义中的名字
+                Kind::Ident if !named && token.text == snippet.name => {
+                    named = true;
+                    (NAME, None)
+                }
+                Kind::Ident => (IDENT, None),
+                _ => continue,
+            };
+
+            let offset = token.text.as_ptr() as usize - text.as_ptr() as usize;
+            let row = text[..offset].matches('\n').count();
+            // 跨行的 token(块注释、多行字符串)每行单独记录
+            for (j, part) in token.text.split('\n').enumerate() {
+                let words: Vec<String> = match key {
+                    Some(key) => [Locale::Zh, Locale::En]
+                        .iter()
+                        .filter_map(|locale| i18n::message(*locale, key))
+                        .flat_map(|message| terms(message, true))
+                        .collect(),
+                    None => terms(part, true),
+                };
+                if words.is_empty() {
+                    continue;
+                }
+                entries.push(Entry {
+                    line: first_line + row + j,
+                    weight,
+                    text: lines[row + j].trim(),
+                    key,
+                    terms: words,
+                });
+            }
+        }
+    }
+    entries
+}
+
+// tr!("base_01.start") 中的字符串是消息的 key
+fn tr_key(before: &[Token<'static>], token: &Token<'static>) -> Option<&'static str> {
+    let mut previous = before
+        .iter()
+        .rev()
+        .filter(|t| t.kind != Kind::Whitespace)
+        .map(|t| t.text);
+    let is_tr = previous.next() == Some("(")
+        && previous.next() == Some("!")
+        && previous.next() == Some("tr");
+    let key = token.text.strip_prefix('"')?.strip_suffix('"')?;
+    is_tr.then_some(key)
+}
+
+/**
+ * 把文字切分成词，英文转成小写
+ *
+ * 中文没有空格分词，连续的中文切成相邻两个字的词(“变量遮蔽”切成 变量 量遮 遮蔽)
+ * unigrams 为 true 时同时记录单个字，建索引时使用，这样只搜一个字时也能找到
+ * 搜索时只有一个字的中文才按单个字搜索
+ */
+fn terms(text: &str, unigrams: bool) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut terms = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        if is_cjk(chars[i]) {
+            while i < chars.len() && is_cjk(chars[i]) {
+                i += 1;
+            }
+            let run = &chars[start..i];
+            if unigrams || run.len() == 1 {
+                terms.extend(run.iter().map(char::to_string));
+            }
+            terms.extend(run.windows(2).map(|pair| pair.iter().collect::<String>()));
+        } else if is_word(chars[i]) {
+            while i < chars.len() && is_word(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            word_terms(&word, &mut terms);
+        } else {
+            i += 1;
+        }
+    }
+    terms
+}
+
+// 标识符按 _ 和大小写拆开，如 HashMap 拆出 hash 和 map，单个字母不记录
+fn word_terms(word: &str, terms: &mut Vec<String>) {
+    let lower = word.to_lowercase();
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut after_lower = false;
+    for c in word.chars() {
+        if c == '_' || (c.is_uppercase() && after_lower) {
+            parts.push(std::mem::take(&mut part));
+        }
+        after_lower = c.is_lowercase() || c.is_ascii_digit();
+        if c != '_' {
+            part.extend(c.to_lowercase());
+        }
+    }
+    parts.push(part);
+
+    if lower.chars().count() >= 2 {
+        terms.push(lower.clone());
+    }
+    if parts.len() > 1 {
+        let parts = parts
+            .into_iter()
+            .filter(|p| p.chars().count() >= 2 && *p != lower);
+        terms.extend(parts);
+    }
+}
+
+fn is_word(c: char) -> bool {
+    (c.is_alphanumeric() || c == '_') && !is_cjk(c)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{f900}'..='\u{faff}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cjk_bigrams_and_unigrams() {
+        assert_eq!(
+            terms("变量遮蔽", true),
+            ["变", "量", "遮", "蔽", "变量", "量遮", "遮蔽"]
+        );
+        // 搜索时不使用单个字，除非只有一个字
+        assert_eq!(terms("变量遮蔽", false), ["变量", "量遮", "遮蔽"]);
+        assert_eq!(terms("宏", false), ["宏"]);
+    }
+
+    #[test]
+    fn mixed_text() {
+        assert_eq!(
+            terms("// 变量 x 的值:{}, Shadowing", true),
+            ["变", "量", "变量", "的", "值", "的值", "shadowing"]
+        );
+    }
+
+    #[test]
+    fn identifiers_split_on_case_and_underscore() {
+        assert_eq!(terms("HashMap", false), ["hashmap", "hash", "map"]);
+        assert_eq!(
+            terms("variable_shadowing", false),
+            ["variable_shadowing", "variable", "shadowing"]
+        );
+        assert_eq!(terms("u8", false), ["u8"]);
+        assert_eq!(terms("x", false), Vec::<String>::new());
+    }
+
+    #[test]
+    fn glossary_translates_both_ways() {
+        let english = groups("shadowing");
+        assert_eq!(english.len(), 1);
+        assert!(english[0].contains(&(String::from("shadowing"), false)));
+        assert!(english[0].contains(&(String::from("遮蔽"), true)));
+
+        let chinese = groups("遮蔽");
+        assert!(chinese[0].contains(&(String::from("shadowing"), true)));
+    }
+
+    #[cfg(feature = "base_01")]
+    #[test]
+    fn shadowing_finds_variable_shadowing_first() {
+        for query in ["shadowing", "变量遮蔽"] {
+            let hits = search(query);
+            let first = hits.first().expect(query);
+            assert_eq!(
+                (first.chapter, first.function),
+                ("base_01", Some("variable_shadowing")),
+                "{}",
+                query
+            );
+        }
+    }
+}
@@ -0,0 +1,517 @@
+// 本地 HTTP 服务 Serve the course over local HTTP
+
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    path::Path,
+    sync::Mutex,
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    book,
+    input::Input,
+    lesson::{self, Lesson},
+    log,
+    runner::{self, Outcome},
+};
+
+// 请求头的最大长度，超过时返回 400
+const MAX_HEADER: usize = 16 * 1024;
+
+/**
+ * 同一时间只运行一个章节
+ * base_11 会读写仓库中的 path.txt，dbg 输出也按章节顺序更容易阅读
+ */
+static RUNNING: Mutex<()> = Mutex::new(());
+
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(content_type: &'static str, body: String) -> Self {
+        Response {
+            status: "200 OK",
+            content_type,
+            body,
+        }
+    }
+
+    fn error(status: &'static str) -> Self {
+        Response {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{}\n", status),
+        }
+    }
+}
+
+/**
+ * 在 127.0.0.1:port 上提供课程页面，只监听本机，不对外开放
+ *
+ * GET  /                目录
+ * GET  /base_XX.html    章节页: 课程函数的说明、代码和运行输出，末尾有运行按钮
+ * GET  /style.css       样式表
+ * GET  /source/base_XX  章节的完整源代码，纯文本
+ * POST /run/base_XX     运行章节，返回全部输出(包括 dbg 输出)，纯文本，只接受 POST
+ *
+ * 只实现 HTTP/1.1 的最小子集: 每个连接一个请求，响应之后关闭连接
+ * 章节需要输入时使用默认答案
+ * Host 不是本机的这个端口、或者 Origin 来自其他网站的请求返回 403，见 is_local
+ */
+pub fn run(port: u16) -> io::Result<()> {
+    let listener = bind(port)?;
+    println!("{}", tr!("serve.listening", listener.local_addr()?.port()));
+    serve(listener);
+    Ok(())
+}
+
+// 在 127.0.0.1:port 上监听，port 为 0 时由系统选择一个空闲的端口
+pub fn bind(port: u16) -> io::Result<TcpListener> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+}
+
+/**
+ * 在已经监听的 listener 上处理请求，每个连接一个线程，一直运行下去
+ */
+pub fn serve(listener: TcpListener) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        thread::spawn(move || {
+            if let Err(e) = handle(stream) {
+                log::error("serve", &tr!("serve.error", e));
+            }
+        });
+    }
+}
+
+fn handle(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let port = stream.local_addr()?.port();
+    let mut reader = BufReader::new(&stream);
+    let response = match read_request(&mut reader)? {
+        Some(request) if !is_local(&request, port) => Response::error("403 Forbidden"),
+        Some(request) => route(&request.method, &request.path),
+        None => Response::error("400 Bad Request"),
+    };
+
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    )?;
+    stream.write_all(response.body.as_bytes())?;
+    stream.flush()
+}
+
+/**
+ * 请求中用到的部分
+ *
+ * path:   去掉查询参数的路径
+ * host:   Host 请求头
+ * origin: Origin 请求头，浏览器在跨站请求和 POST 时会带上
+ */
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    host: Option<String>,
+    origin: Option<String>,
+}
+
+/**
+ * 读取请求行和请求头，请求体(如果有)读出来丢弃
+ * 请求格式不对、或者请求行加上请求头超过 MAX_HEADER 时返回 None
+ */
+fn read_request(reader: &mut impl BufRead) -> io::Result<Option<Request>> {
+    // 请求行也算在 MAX_HEADER 之内，没有换行的超长请求行不会一直读下去
+    let mut head = reader.by_ref().take(MAX_HEADER as u64);
+    let mut request_line = String::new();
+    head.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Ok(None);
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Ok(None);
+    }
+
+    let mut request = Request {
+        method: method.to_string(),
+        path: target.split('?').next().unwrap_or_default().to_string(),
+        host: None,
+        origin: None,
+    };
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        // 读到 MAX_HEADER 还没有空行时 read_line 返回 0
+        if head.read_line(&mut line)? == 0 || !line.ends_with('\n') {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.parse().unwrap_or(0);
+            } else if name.eq_ignore_ascii_case("host") {
+                request.host = Some(value.to_string());
+            } else if name.eq_ignore_ascii_case("origin") {
+                request.origin = Some(value.to_string());
+            }
+        }
+    }
+    io::copy(&mut reader.take(length), &mut io::sink())?;
+
+    Ok(Some(request))
+}
+
+/**
+ * 请求是否发给本机的这个端口
+ *
+ * Host 必须是 127.0.0.1:port 或 localhost:port，带有 Origin 时也必须是这两个地址之一，
+ * 这样其他网站的页面无法借助 DNS 重绑定读取课程页面，也无法跨站 POST /run/ 在本机运行章节
+ */
+fn is_local(request: &Request, port: u16) -> bool {
+    let local = |authority: &str| {
+        [format!("127.0.0.1:{}", port), format!("localhost:{}", port)]
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(authority))
+    };
+    let origin = request
+        .origin
+        .as_deref()
+        .is_none_or(|origin| origin.strip_prefix("http://").is_some_and(local));
+    request.host.as_deref().is_some_and(local) && origin
+}
+
+fn route(method: &str, path: &str) -> Response {
+    let html = "text/html; charset=utf-8";
+    let text = "text/plain; charset=utf-8";
+
+    // 运行章节会执行代码、读写 path.txt，不能由链接或者 <img> 这样的 GET 触发
+    if let Some(id) = path.strip_prefix("/run/") {
+        if method != "POST" {
+            return Response::error("405 Method Not Allowed");
+        }
+        return match chapter(id) {
+            Some(lesson) => Response::ok(text, run_chapter(lesson)),
+            None => Response::error("404 Not Found"),
+        };
+    }
+    if method != "GET" {
+        return Response::error("405 Method Not Allowed");
+    }
+
+    match path {
+        "/" | "/index.html" => Response::ok(html, book::index_page()),
+        "/style.css" => Response::ok("text/css; charset=utf-8", book::STYLE.to_string()),
+        _ => {
+            if let Some(lesson) = path
+                .strip_prefix('/')
+                .and_then(|p| p.strip_suffix(".html"))
+                .and_then(chapter)
+            {
+                // 章节页中包含每个课程函数的运行输出
+                let _running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+                let page = book::chapter_page(lesson, true);
+                return Response::ok(html, page);
+            }
+            let source = path
+                .strip_prefix("/source/")
+                .and_then(chapter)
+                .and_then(|lesson| {
+                    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                        .join("src")
+                        .join(format!("{}.rs", lesson.id()));
+                    fs::read_to_string(path).ok()
+                });
+            match source {
+                Some(source) => Response::ok(text, source),
+                None => Response::error("404 Not Found"),
+            }
+        }
+    }
+}
+
+// 只接受编译进来的章节 id，路径中的其他内容一律 404
+fn chapter(id: &str) -> Option<&'static dyn Lesson> {
+    lesson::registry().iter().copied().find(|l| l.id() == id)
+}
+
+fn run_chapter(lesson: &'static dyn Lesson) -> String {
+    let _running = RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+    let result = runner::run_captured(lesson, &mut Input::Defaults, false);
+    let mut output = result.output.unwrap_or_default();
+    if let Outcome::Panicked { message, location } = result.outcome {
+        let location = location.unwrap_or_default();
+        output.push_str(&tr!("runner.panicked", lesson.id(), location, message));
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    // 启动一个监听 127.0.0.1 上随机端口的服务，返回端口
+    fn start() -> u16 {
+        let listener = bind(0).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || serve(listener));
+        port
+    }
+
+    // 发送原始的请求，返回状态行和响应体
+    fn send(port: u16, request: &str) -> (String, String) {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.lines().next().unwrap().to_string();
+        (status, body.to_string())
+    }
+
+    fn get(port: u16, path: &str) -> (String, String) {
+        send(
+            port,
+            &format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1:{}\r\n\r\n", path, port),
+        )
+    }
+
+    fn post(port: u16, path: &str) -> (String, String) {
+        send(
+            port,
+            &format!(
+                "POST {} HTTP/1.1\r\nHost: localhost:{}\r\nOrigin: http://localhost:{}\r\nContent-Length: 0\r\n\r\n",
+                path, port, port
+            ),
+        )
+    }
+
+    fn request(text: &str) -> Option<Request> {
+        read_request(&mut Cursor::new(text.as_bytes())).unwrap()
+    }
+
+    fn local(method: &str, path: &str, host: Option<&str>, origin: Option<&str>) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            host: host.map(String::from),
+            origin: origin.map(String::from),
+        }
+    }
+
+    #[test]
+    fn serves_over_loopback() {
+        let port = start();
+
+        let (status, body) = get(port, "/");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert!(body.contains("<html"));
+
+        let (status, body) = get(port, "/style.css");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(body, book::STYLE);
+
+        let (status, _) = get(port, "/base_99.html");
+        assert_eq!(status, "HTTP/1.1 404 Not Found");
+        let (status, _) = get(port, "/source/..%2fCargo.toml");
+        assert_eq!(status, "HTTP/1.1 404 Not Found");
+        let (status, _) = post(port, "/run/base_99");
+        assert_eq!(status, "HTTP/1.1 404 Not Found");
+
+        let (status, _) = send(
+            port,
+            &format!("DELETE / HTTP/1.1\r\nHost: 127.0.0.1:{}\r\n\r\n", port),
+        );
+        assert_eq!(status, "HTTP/1.1 405 Method Not Allowed");
+        let (status, _) = send(port, "GET /\r\n\r\n");
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        let (status, _) = send(port, "hello\r\n\r\n");
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+    }
+
+    #[cfg(feature = "base_03")]
+    #[test]
+    fn serves_chapter_page_and_source() {
+        let port = start();
+
+        let (status, body) = get(port, "/base_03.html");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert!(body.contains("base_03"));
+        assert!(body.contains("run-button"));
+
+        let (status, body) = get(port, "/source/base_03");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert!(body.contains("impl Lesson for Chapter"));
+    }
+
+    #[cfg(feature = "base_07")]
+    #[test]
+    fn runs_chapter() {
+        let port = start();
+
+        let (status, body) = post(port, "/run/base_07");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert!(body.contains("Circle { x: 10.0, y: 10.0, radius: 5.0 }"));
+        assert!(body.contains("area:78.53981633974483"));
+
+        let (status, _) = get(port, "/run/base_07");
+        assert_eq!(status, "HTTP/1.1 405 Method Not Allowed");
+    }
+
+    // 其他网站的页面跨站 POST，或者通过 DNS 重绑定把自己的域名指向 127.0.0.1
+    #[test]
+    fn rejects_requests_not_addressed_to_the_server() {
+        let port = start();
+
+        let forged = [
+            format!("POST /run/base_01 HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nOrigin: http://evil.example\r\n\r\n", port),
+            String::from("POST /run/base_01 HTTP/1.1\r\nHost: evil.example\r\n\r\n"),
+            String::from("GET / HTTP/1.1\r\nHost: evil.example:80\r\n\r\n"),
+            String::from("POST /run/base_01 HTTP/1.0\r\n\r\n"),
+        ];
+        for request in forged {
+            let (status, body) = send(port, &request);
+            assert_eq!(status, "HTTP/1.1 403 Forbidden", "{}", request);
+            assert_eq!(body, "403 Forbidden\n");
+        }
+    }
+
+    #[test]
+    fn accepts_only_the_bound_loopback_address() {
+        let port = 8080;
+        assert!(is_local(
+            &local("GET", "/", Some("127.0.0.1:8080"), None),
+            port
+        ));
+        assert!(is_local(
+            &local("GET", "/", Some("LOCALHOST:8080"), None),
+            port
+        ));
+        assert!(is_local(
+            &local(
+                "POST",
+                "/run/base_01",
+                Some("localhost:8080"),
+                Some("http://127.0.0.1:8080")
+            ),
+            port
+        ));
+
+        assert!(!is_local(&local("GET", "/", None, None), port));
+        assert!(!is_local(
+            &local("GET", "/", Some("127.0.0.1:9090"), None),
+            port
+        ));
+        assert!(!is_local(&local("GET", "/", Some("127.0.0.1"), None), port));
+        assert!(!is_local(
+            &local("GET", "/", Some("rebind.example:8080"), None),
+            port
+        ));
+        assert!(!is_local(
+            &local(
+                "POST",
+                "/run/base_01",
+                Some("127.0.0.1:8080"),
+                Some("http://evil.example")
+            ),
+            port
+        ));
+        assert!(!is_local(
+            &local("POST", "/run/base_01", Some("127.0.0.1:8080"), Some("null")),
+            port
+        ));
+        assert!(!is_local(
+            &local(
+                "POST",
+                "/run/base_01",
+                Some("127.0.0.1:8080"),
+                Some("https://127.0.0.1:8080")
+            ),
+            port
+        ));
+    }
+
+    #[test]
+    fn reads_request_line_and_skips_body() {
+        assert_eq!(
+            request("GET /base_01.html?x=1 HTTP/1.1\r\nhost:  127.0.0.1:8080 \r\nOrigin: http://localhost:8080\r\n\r\n"),
+            Some(local(
+                "GET",
+                "/base_01.html",
+                Some("127.0.0.1:8080"),
+                Some("http://localhost:8080")
+            ))
+        );
+
+        // 请求体读出来丢弃，后面的内容不受影响
+        let mut reader =
+            Cursor::new(&b"POST /run/base_07 HTTP/1.0\r\nContent-Length: 5\r\n\r\nhellorest"[..]);
+        let parsed = read_request(&mut reader).unwrap();
+        assert_eq!(parsed, Some(local("POST", "/run/base_07", None, None)));
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "rest");
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        assert_eq!(request(""), None);
+        assert_eq!(request("GET /\r\n\r\n"), None);
+        assert_eq!(request("GET / FTP/1.0\r\n\r\n"), None);
+        // 请求头没有结束
+        assert_eq!(request("GET / HTTP/1.1\r\nHost: x\r\n"), None);
+        // 请求头太长
+        let long = format!("GET / HTTP/1.1\r\nX: {}\r\n\r\n", "a".repeat(MAX_HEADER));
+        assert_eq!(request(&long), None);
+        // 没有换行的请求行最多读 MAX_HEADER 字节
+        let mut endless = Cursor::new(vec![b'a'; 4 * MAX_HEADER]);
+        assert_eq!(read_request(&mut endless).unwrap(), None);
+        assert_eq!(endless.position(), MAX_HEADER as u64);
+    }
+
+    #[test]
+    fn routes_by_method_and_path() {
+        assert_eq!(route("GET", "/").status, "200 OK");
+        assert_eq!(route("GET", "/index.html").status, "200 OK");
+        let css = route("GET", "/style.css");
+        assert_eq!(css.content_type, "text/css; charset=utf-8");
+
+        assert_eq!(route("DELETE", "/").status, "405 Method Not Allowed");
+        assert_eq!(
+            route("PUT", "/run/base_07").status,
+            "405 Method Not Allowed"
+        );
+        assert_eq!(
+            route("GET", "/run/base_07").status,
+            "405 Method Not Allowed"
+        );
+        assert_eq!(route("POST", "/run/base_99").status, "404 Not Found");
+        assert_eq!(
+            route("GET", "/source/../Cargo.toml").status,
+            "404 Not Found"
+        );
+        assert_eq!(route("GET", "/base_01").status, "404 Not Found");
+        assert_eq!(route("GET", "/nope.html").status, "404 Not Found");
+    }
+}
@@ -0,0 +1,163 @@
+// 交互模式 Interactive shell
+
+use std::io::{self, Write};
+
+use crate::{
+    input::Input,
+    lesson,
+    output::Output,
+    runner::{self, Outcome},
+    search, source,
+};
+
+/**
+ * 交互模式，在提示符下输入命令浏览和运行章节，不需要每次重新启动程序
+ *
+ * 提示符中显示当前章节，next / prev 以当前章节为起点
+ * 本次会话输入过的命令保存在 history 中，退出后不保留
+ */
+struct Shell {
+    current: usize,
+    history: Vec<String>,
+    input: Input,
+}
+
+pub fn run() {
+    // 用 --no-default-features 编译时可能一个章节都没有，没有当前章节可以显示和运行
+    if lesson::registry().is_empty() {
+        println!("{}", tr!("shell.no_chapters"));
+        return;
+    }
+    let mut shell = Shell {
+        current: 0,
+        history: Vec::new(),
+        input: Input::Stdin,
+    };
+    println!("{}", tr!("shell.welcome"));
+
+    loop {
+        print!("rust-learn {}> ", lesson::registry()[shell.current].id());
+        let _ = io::stdout().flush();
+
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            // 输入结束(如 Ctrl-D)时退出
+            Ok(0) | Err(_) => {
+                println!();
+                break;
+            }
+            Ok(_) => {}
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        shell.history.push(line.to_string());
+
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let arg = words.next();
+        match command {
+            "help" => println!("{}", tr!("shell.help")),
+            "ls" => shell.ls(arg),
+            "run" => shell.run(arg),
+            "next" => shell.step(1),
+            "prev" => shell.step(-1),
+            // 搜索词可以包含空格，取命令之后的整行
+            "search" => match arg {
+                Some(_) => {
+                    let query = line["search".len()..].trim();
+                    search::print(query, &search::search(query));
+                }
+                None => println!("{}", tr!("shell.missing_arg", command)),
+            },
+            "source" => match arg {
+                Some(path) => show_source(path),
+                None => println!("{}", tr!("shell.missing_arg", command)),
+            },
+            "history" => {
+                for (i, line) in shell.history.iter().enumerate() {
+                    println!("{:>4}  {}", i + 1, line);
+                }
+            }
+            "quit" | "exit" => break,
+            other => println!("{}", tr!("shell.unknown_command", other)),
+        }
+    }
+}
+
+impl Shell {
+    // 不带参数时列出所有章节，当前章节用 * 标出；带章节 id 时列出其中的课程函数
+    fn ls(&self, id: Option<&str>) {
+        let Some(id) = id else {
+            for (i, lesson) in lesson::registry().iter().enumerate() {
+                let marker = if i == self.current { '*' } else { ' ' };
+                println!("{} {}  {}", marker, lesson.id(), lesson.title());
+            }
+            return;
+        };
+        match lesson::lookup(id) {
+            Some((lesson, None)) => {
+                for section in lesson.sections() {
+                    println!("  {}::{}", lesson.id(), section.name);
+                }
+            }
+            _ => println!("{}", tr!("shell.unknown_target", id)),
+        }
+    }
+
+    // 运行当前章节、指定的章节(如 base_07)或者单个课程函数(如 base_06::matches_macro_example)
+    fn run(&mut self, target: Option<&str>) {
+        let (lesson, section) = match target {
+            None => (lesson::registry()[self.current], None),
+            Some(target) => match lesson::lookup(target) {
+                Some(found) => found,
+                None => {
+                    println!("{}", tr!("shell.unknown_target", target));
+                    return;
+                }
+            },
+        };
+        self.current = lesson::position(lesson.id()).unwrap_or(self.current);
+
+        let mut out = Output::console();
+        let outcome = match section {
+            Some(section) => runner::run_section(section, &mut out, &mut self.input),
+            None => runner::run(lesson, &mut out, &mut self.input, false).outcome,
+        };
+        if let Outcome::Panicked { message, location } = outcome {
+            let location = location.unwrap_or_else(|| String::from("<unknown>"));
+            println!("{}", tr!("runner.panicked", lesson.id(), location, message));
+        }
+    }
+
+    // 移动到下一个(step 为 1)或上一个(step 为 -1)章节并运行
+    fn step(&mut self, step: isize) {
+        let Some(next) = self
+            .current
+            .checked_add_signed(step)
+            .filter(|&i| i < lesson::registry().len())
+        else {
+            let key = if step > 0 {
+                "shell.last"
+            } else {
+                "shell.first"
+            };
+            println!("{}", tr!(key));
+            return;
+        };
+        self.current = next;
+        self.run(None);
+    }
+}
+
+// 打印函数的源代码，如 base_03::quote，不是课程函数的辅助函数(如 base_03::takes_ownership)也可以
+fn show_source(path: &str) {
+    let found = path
+        .split_once("::")
+        .and_then(|(id, name)| Some((id, source::find(id, name)?)));
+    match found {
+        Some((id, snippet)) => source::print(&mut Output::console(), id, snippet),
+        None => println!("{}", tr!("shell.no_source", path)),
+    }
+}
@@ -0,0 +1,336 @@
+// 输出快照 Golden output snapshots
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    i18n::{self, Locale},
+    input::Input,
+    lesson::Lesson,
+    log::Filter,
+    output::Output,
+    runner::{self, Outcome},
+};
+
+/**
+ * 快照保存在仓库的 snapshots 目录下，每个章节一个文件，如 snapshots/base_01.txt
+ * 下面读写快照的函数都由调用方传入这个目录
+ */
+pub fn snapshot_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("snapshots")
+}
+
+fn snapshot_path(dir: &Path, lesson: &dyn Lesson) -> PathBuf {
+    dir.join(format!("{}.txt", lesson.id()))
+}
+
+// 快照总是以中文记录，与 --lang 和环境变量无关
+const LOCALE: Locale = Locale::Zh;
+
+// 输出中出现的仓库根目录(如 base_11 打开的 path.txt)替换为 <repo>
+const ROOT: &str = env!("CARGO_MANIFEST_DIR");
+
+/**
+ * 运行章节并捕获全部输出，返回规范化之后的文本
+ *
+ * 需要输入的章节总是使用默认答案，保证每次运行的输入相同
+ * 章节 panic 时，panic 信息作为最后一行记录在快照中
+ * 以 LOCALE 和默认的日志规则运行，与 -v / -q、--log 无关，两者都只作用于这次运行，不改变全局的设置
+ */
+pub fn capture(lesson: &'static dyn Lesson) -> String {
+    let mut buf = Vec::new();
+    let result = i18n::with_locale(LOCALE, || {
+        let mut out = Output::new(&mut buf).with_filter(Filter::default());
+        runner::run(lesson, &mut out, &mut Input::Defaults, false)
+    });
+    let mut text = String::from_utf8_lossy(&buf).into_owned();
+    if let Outcome::Panicked { message, location } = result.outcome {
+        text.push_str(&format!(
+            "\npanicked at {}: {}\n",
+            location.unwrap_or_default(),
+            message
+        ));
+    }
+    normalize(&text, lesson.unordered())
+}
+
+// 记录章节的输出快照
+pub fn record(dir: &Path, lesson: &'static dyn Lesson) -> io::Result<PathBuf> {
+    let path = snapshot_path(dir, lesson);
+    fs::create_dir_all(dir)?;
+    fs::write(&path, capture(lesson))?;
+    Ok(path)
+}
+
+pub enum Verdict {
+    Match,
+    Missing,
+    Mismatch(Vec<String>),
+}
+
+// 重新运行章节，与已记录的快照比较
+pub fn verify(dir: &Path, lesson: &'static dyn Lesson) -> io::Result<Verdict> {
+    let expected = match fs::read_to_string(snapshot_path(dir, lesson)) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Verdict::Missing),
+        Err(e) => return Err(e),
+    };
+    let actual = capture(lesson);
+    if expected == actual {
+        Ok(Verdict::Match)
+    } else {
+        Ok(Verdict::Mismatch(diff(&expected, &actual)))
+    }
+}
+
+/**
+ * 记录选中章节的快照，逐个打印写出的文件，出错时停止并返回错误
+ */
+pub fn record_all(dir: &Path, lessons: &[&'static dyn Lesson]) -> io::Result<()> {
+    for lesson in lessons {
+        let path = record(dir, *lesson).map_err(|e| {
+            let message = tr!("cli.cannot_record", lesson.id(), e);
+            io::Error::new(e.kind(), message)
+        })?;
+        println!("{}", tr!("snapshot.recorded", path.display()));
+    }
+    Ok(())
+}
+
+/**
+ * 重新运行选中的章节并与快照比较，打印每个章节的结果以及不同的行，返回与快照不一致的章节数
+ */
+pub fn verify_all(dir: &Path, lessons: &[&'static dyn Lesson]) -> usize {
+    let mut failed = 0;
+    for lesson in lessons {
+        match verify(dir, *lesson) {
+            Ok(Verdict::Match) => println!("{}", tr!("snapshot.ok", lesson.id())),
+            Ok(Verdict::Missing) => {
+                failed += 1;
+                println!("{}", tr!("snapshot.missing", lesson.id(), lesson.id()));
+            }
+            Ok(Verdict::Mismatch(lines)) => {
+                failed += 1;
+                println!("{}", tr!("snapshot.mismatch", lesson.id()));
+                for line in lines {
+                    println!("    {}", line);
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                println!("{}", tr!("snapshot.error", lesson.id(), e));
+            }
+        }
+    }
+    if failed > 0 {
+        eprintln!("{}", tr!("snapshot.differ", failed));
+    }
+    failed
+}
+
+/**
+ * 规范化输出，消除每次运行都可能不同的内容
+ *
+ * 1. HashMap 的 {:?} 输出，如 {"k1": 1, "k2": 2}，按条目排序
+ * 2. 章节声明的无序行前缀(如遍历 HashMap 打印的 k:...)，连续的这些行排序
+ * 3. 文件描述符编号 fd: 3 替换为 fd: _，仓库根目录替换为 <repo>
+ */
+pub fn normalize(text: &str, unordered: &[&str]) -> String {
+    let mut lines: Vec<String> = text
+        .lines()
+        .map(|line| mask_fd(&sort_map_line(line).replace(ROOT, "<repo>")))
+        .collect();
+
+    let is_unordered = |line: &str| unordered.iter().any(|p| line.starts_with(p));
+    let mut i = 0;
+    while i < lines.len() {
+        if is_unordered(&lines[i]) {
+            let start = i;
+            while i < lines.len() && is_unordered(&lines[i]) {
+                i += 1;
+            }
+            lines[start..i].sort();
+        } else {
+            i += 1;
+        }
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+// 整行都是 {..} 形式的 map 输出时，对其中的条目排序
+fn sort_map_line(line: &str) -> String {
+    let inner = match line.strip_prefix('{').and_then(|l| l.strip_suffix('}')) {
+        Some(inner) if inner.contains(": ") => inner,
+        _ => return line.to_string(),
+    };
+    let mut entries = split_top_level(inner);
+    entries.sort_unstable();
+    format!("{{{}}}", entries.join(", "))
+}
+
+// 按最外层的 ", " 切分，忽略括号和字符串内部的逗号
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut in_str = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        if in_str {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_str = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_str = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 && s[i..].starts_with(", ") => {
+                parts.push(&s[start..i]);
+                start = i + 2;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn mask_fd(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(pos) = rest.find("fd: ") {
+        result.push_str(&rest[..pos + 4]);
+        rest = &rest[pos + 4..];
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits > 0 {
+            result.push('_');
+            rest = &rest[digits..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/**
+ * 逐行比较，返回不同的行，- 为快照中的内容，+ 为本次运行的输出
+ */
+fn diff(expected: &str, actual: &str) -> Vec<String> {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    let mut lines = Vec::new();
+    for i in 0..expected.len().max(actual.len()) {
+        let (e, a) = (expected.get(i), actual.get(i));
+        if e == a {
+            continue;
+        }
+        if let Some(e) = e {
+            lines.push(format!("{:>4} - {}", i + 1, e));
+        }
+        if let Some(a) = a {
+            lines.push(format!("{:>4} + {}", i + 1, a));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lesson;
+
+    #[test]
+    fn sort_map_line_sorts_entries() {
+        assert_eq!(
+            sort_map_line(r#"{"k2": 2, "k1": 1, "k3": 3}"#),
+            r#"{"k1": 1, "k2": 2, "k3": 3}"#
+        );
+        // 值中的逗号不切分
+        assert_eq!(
+            sort_map_line(r#"{"b": [1, 2], "a": ("x, y", 3)}"#),
+            r#"{"a": ("x, y", 3), "b": [1, 2]}"#
+        );
+        // 不是 map 的行原样保留
+        assert_eq!(sort_map_line("{3, 1, 2}"), "{3, 1, 2}");
+        assert_eq!(
+            sort_map_line(r#"map: {"b": 1, "a": 2}"#),
+            r#"map: {"b": 1, "a": 2}"#
+        );
+        assert_eq!(sort_map_line("{}"), "{}");
+    }
+
+    #[test]
+    fn split_top_level_ignores_nested_commas() {
+        assert_eq!(split_top_level("a, b, c"), ["a", "b", "c"]);
+        assert_eq!(
+            split_top_level("f(1, 2), [3, 4], {5, 6}"),
+            ["f(1, 2)", "[3, 4]", "{5, 6}"]
+        );
+        assert_eq!(
+            split_top_level(r#""a, b", "c\", d""#),
+            [r#""a, b""#, r#""c\", d""#]
+        );
+        // 没有空格的逗号不是分隔符
+        assert_eq!(split_top_level("1,2, 3"), ["1,2", "3"]);
+        assert_eq!(split_top_level(""), [""]);
+    }
+
+    #[test]
+    fn mask_fd_hides_descriptor_numbers() {
+        assert_eq!(
+            mask_fd("File { fd: 3, path: \"a\" } File { fd: 12, read: true }"),
+            "File { fd: _, path: \"a\" } File { fd: _, read: true }"
+        );
+        assert_eq!(mask_fd("fd: x"), "fd: x");
+        assert_eq!(mask_fd("fd: "), "fd: ");
+        assert_eq!(mask_fd("no descriptor"), "no descriptor");
+    }
+
+    #[test]
+    fn normalize_masks_paths_and_sorts_unordered_lines() {
+        let text = format!(
+            "start\nk:b\nk:a\nmiddle\nk:d\nk:c\nfile: File {{ fd: 4, path: \"{}/path.txt\" }}\n{{\"y\": 2, \"x\": 1}}",
+            ROOT
+        );
+        assert_eq!(
+            normalize(&text, &["k:"]),
+            "start\nk:a\nk:b\nmiddle\nk:c\nk:d\nfile: File { fd: _, path: \"<repo>/path.txt\" }\n{\"x\": 1, \"y\": 2}\n"
+        );
+        assert_eq!(normalize("b\na", &[]), "b\na\n");
+        assert_eq!(normalize("", &[]), "\n");
+    }
+
+    #[test]
+    fn diff_reports_changed_added_and_removed_lines() {
+        assert!(diff("a\nb\n", "a\nb\n").is_empty());
+        assert_eq!(diff("a\nb\n", "a\nc\n"), ["   2 - b", "   2 + c"]);
+        assert_eq!(diff("a\n", "a\nb\n"), ["   2 + b"]);
+        assert_eq!(diff("a\nb\n", "a\n"), ["   2 - b"]);
+    }
+
+    // 与 rust-learn verify 相同: 每个编译进来的章节的输出都与 snapshots 目录下的快照一致
+    #[test]
+    fn every_snapshot_matches() {
+        let dir = snapshot_dir();
+        for lesson in lesson::registry() {
+            match verify(&dir, *lesson).unwrap() {
+                Verdict::Match => {}
+                Verdict::Missing => panic!("{}: no snapshot", lesson.id()),
+                Verdict::Mismatch(lines) => {
+                    panic!("{} differs:\n{}", lesson.id(), lines.join("\n"))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// 课程源代码 Lesson source snippets
+
+use crate::{highlight, output::Output};
+
+/**
+ * 课程章节中的一个函数，构建时由 build.rs 从章节的源代码中切分出来
+ *
+ * name: 函数名，如 repeat_mutable_borrow
+ * line: code 在源文件中开始的行号
+ * doc:  紧挨在函数前面的注释(// 或 /** */)，没有时为空
+ * code: 函数本身，从属性(如 #[allow(unused)])开始到函数体结束的 } 为止
+ */
+pub struct Snippet {
+    pub name: &'static str,
+    pub line: usize,
+    pub doc: &'static str,
+    pub code: &'static str,
+}
+
+/**
+ * Lesson 特征的方法也在章节的源代码中，它们不是课程内容
+ */
+pub const LESSON_METHODS: &[&str] = &[
+    "id",
+    "title",
+    "topics",
+    "sections",
+    "run_with",
+    "banners",
+    "unordered",
+    "serial",
+];
+
+include!(concat!(env!("OUT_DIR"), "/sources.rs"));
+
+// 章节中所有的函数，按在源文件中出现的顺序排列
+pub fn snippets(id: &str) -> &'static [Snippet] {
+    SOURCES
+        .iter()
+        .find(|(chapter, _)| *chapter == id)
+        .map_or(&[], |(_, snippets)| snippets)
+}
+
+// 按函数名查找，同名的方法(如多个 impl 中的 new)取第一个
+pub fn find(id: &str, name: &str) -> Option<&'static Snippet> {
+    snippets(id).iter().find(|snippet| snippet.name == name)
+}
+
+/**
+ * 打印函数的注释和代码，第一行标出所在的文件和行号
+ * 输出到终端时高亮显示
+ *
+ * ---------- src/base_03.rs:77 ----------
+ */
+pub fn print(out: &mut Output, id: &str, snippet: &Snippet) {
+    outln!(out, "---------- src/{}.rs:{} ----------", id, snippet.line);
+    for text in [snippet.doc, snippet.code] {
+        if text.is_empty() {
+            continue;
+        }
+        if out.color() {
+            outln!(out, "{}", highlight::highlight(text));
+        } else {
+            outln!(out, "{}", text);
+        }
+    }
+}
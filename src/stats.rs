@@ -0,0 +1,169 @@
+// 运行统计 Timing and heap allocation statistics
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+/**
+ * 统计堆分配的全局分配器，实际的分配仍然交给 System
+ *
+ * 计数按线程分开记录，这样同时运行的其他线程不会影响当前章节的统计
+ * realloc 也算作一次分配，字节数为新的大小
+ *
+ * 库不替换使用者的全局分配器，由 rust-learn 程序在 main.rs 中安装，
+ * 没有安装时 measure 只统计耗时，分配次数和字节数都是 0
+ */
+pub struct Counting;
+
+thread_local! {
+    static ALLOCS: Cell<u64> = const { Cell::new(0) };
+    static BYTES: Cell<u64> = const { Cell::new(0) };
+    // 为 true 时不计数，见 untracked
+    static PAUSED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn record(size: usize) {
+    // 线程退出时 thread_local 可能已经销毁，这时忽略即可
+    if PAUSED.try_with(Cell::get).unwrap_or(true) {
+        return;
+    }
+    let _ = ALLOCS.try_with(|a| a.set(a.get() + 1));
+    let _ = BYTES.try_with(|b| b.set(b.get() + size as u64));
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/**
+ * 一段代码的运行统计：耗时、分配次数、分配的字节数
+ */
+#[derive(Clone, Copy, Default)]
+pub struct Usage {
+    pub duration: Duration,
+    pub allocs: u64,
+    pub bytes: u64,
+}
+
+// 运行 f 并统计当前线程上的耗时和堆分配
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Usage) {
+    let allocs = ALLOCS.with(Cell::get);
+    let bytes = BYTES.with(Cell::get);
+    let start = Instant::now();
+
+    let result = f();
+
+    let usage = Usage {
+        duration: start.elapsed(),
+        allocs: ALLOCS.with(Cell::get) - allocs,
+        bytes: BYTES.with(Cell::get) - bytes,
+    };
+    (result, usage)
+}
+
+/**
+ * 运行 f，其间当前线程上的堆分配不计入统计
+ *
+ * 用于课程代码之外的开销: 写入 Output(捕获输出时缓冲区的扩容、stdout 第一次输出时分配的缓冲区)、
+ * 消息目录的查找和格式化、运行时打印的日志，这些在课程原来直接 println! 时都不存在，
+ * 排除之后同一个课程函数串行、--jobs 并行和捕获输出时的统计相同
+ */
+pub fn untracked<R>(f: impl FnOnce() -> R) -> R {
+    // f panic 时也要恢复
+    struct Resume(bool);
+
+    impl Drop for Resume {
+        fn drop(&mut self) {
+            let _ = PAUSED.try_with(|p| p.set(self.0));
+        }
+    }
+
+    let _resume = Resume(PAUSED.with(|p| p.replace(true)));
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::Output;
+
+    // 单元测试也安装计数分配器，和 rust-learn 程序一样
+    #[global_allocator]
+    static GLOBAL: Counting = Counting;
+
+    #[test]
+    fn measure_counts_allocations() {
+        let (v, usage) = measure(|| Vec::<u64>::with_capacity(100));
+        assert_eq!((usage.allocs, usage.bytes), (1, 800));
+        drop(v);
+
+        let ((), usage) = measure(|| {
+            let mut s = String::with_capacity(4);
+            s.push_str("12345");
+        });
+        // with_capacity 一次，push_str 扩容 realloc 一次
+        assert_eq!((usage.allocs, usage.bytes), (2, 4 + 8));
+
+        let ((), usage) = measure(|| ());
+        assert_eq!((usage.allocs, usage.bytes), (0, 0));
+    }
+
+    #[test]
+    fn untracked_allocations_are_excluded() {
+        let ((), usage) = measure(|| {
+            untracked(|| drop(vec![0u8; 100]));
+            drop(vec![0u8; 10]);
+        });
+        assert_eq!((usage.allocs, usage.bytes), (1, 10));
+    }
+
+    #[test]
+    fn output_buffer_growth_is_excluded() {
+        let mut buf = Vec::new();
+        let mut out = Output::new(&mut buf);
+        let ((), usage) = measure(|| {
+            for i in 0..1000 {
+                outln!(out, "line {}", i);
+            }
+        });
+        assert_eq!((usage.allocs, usage.bytes), (0, 0));
+        drop(out);
+        assert_eq!(buf.iter().filter(|b| **b == b'\n').count(), 1000);
+    }
+
+    // with_capacity(1000) 一次分配 1000 个 i32，之后的 push 和输出都不再分配
+    #[cfg(feature = "base_09")]
+    #[test]
+    fn lesson_allocations_do_not_depend_on_the_output() {
+        use std::io;
+
+        use crate::base_09;
+
+        let mut buf = Vec::new();
+        let mut out = Output::new(&mut buf);
+        let ((), captured) = measure(|| base_09::vector_capacity_example(&mut out));
+        let mut out = Output::new(io::sink());
+        let ((), sink) = measure(|| base_09::vector_capacity_example(&mut out));
+        assert_eq!((captured.allocs, captured.bytes), (1, 4000));
+        assert_eq!((sink.allocs, sink.bytes), (1, 4000));
+    }
+}
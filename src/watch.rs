@@ -0,0 +1,163 @@
+// 监视模式 Watch mode
+
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    i18n::{self, Locale},
+    lesson::{self, Lesson},
+    log,
+};
+
+// 两次检查之间的间隔
+const INTERVAL: Duration = Duration::from_millis(500);
+
+/**
+ * 监视 src 目录，文件保存之后用本地 cargo 重新编译，再重新运行改动过的章节
+ *
+ * 只比较文件的修改时间，不依赖 inotify 之类的系统通知，所有平台都一样
+ * 改动的是章节文件 src/base_XX.rs 时只重新运行这些章节，改动其他文件时重新运行所有监视的章节
+ * 编译错误直接显示在终端中，修改之后会再次编译
+ * 章节由新编译出来的程序运行，不读取输入，需要输入时使用默认答案
+ *
+ * 一直运行到按 Ctrl-C 为止，只有无法读取 src 目录时才返回错误
+ */
+pub fn run(lessons: &[&'static dyn Lesson]) -> io::Result<()> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let src = root.join("src");
+    let mut last = scan(&src)?;
+    println!("{}", tr!("watch.started", src.display()));
+
+    loop {
+        thread::sleep(INTERVAL);
+        let now = scan(&src)?;
+        if now == last {
+            continue;
+        }
+        let changed = changed(&last, &now);
+        last = now;
+
+        let mut chapters = Vec::new();
+        let mut shared = false;
+        for path in &changed {
+            match chapter_id(&src, path) {
+                Some(id) => chapters.push(id),
+                None => shared = true,
+            }
+        }
+        let targets: Vec<&str> = lessons
+            .iter()
+            .map(|lesson| lesson.id())
+            .filter(|id| shared || chapters.iter().any(|c| c == id))
+            .collect();
+        // 改动的章节都没有被监视
+        if targets.is_empty() {
+            continue;
+        }
+
+        for path in &changed {
+            let path = path.strip_prefix(root).unwrap_or(path);
+            println!("{}", tr!("watch.changed", path.display()));
+        }
+        if !build(root) {
+            println!("{}", tr!("watch.build_failed"));
+            continue;
+        }
+        println!("{}", tr!("watch.rerun", targets.join(", ")));
+        rerun(root, &targets);
+        println!("{}", tr!("watch.waiting"));
+    }
+}
+
+// src/base_XX.rs 是章节文件，返回章节 id
+fn chapter_id(src: &Path, path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let chapter = path.parent() == Some(src)
+        && stem.starts_with("base_")
+        && path.extension().is_some_and(|ext| ext == "rs");
+    chapter.then(|| stem.to_string())
+}
+
+// 目录下所有文件(包括子目录)的修改时间
+fn scan(dir: &Path) -> io::Result<BTreeMap<PathBuf, SystemTime>> {
+    let mut files = BTreeMap::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            files.extend(scan(&entry.path())?);
+        } else {
+            files.insert(entry.path(), metadata.modified()?);
+        }
+    }
+    Ok(files)
+}
+
+// 新增、删除和修改时间变了的文件
+fn changed(
+    before: &BTreeMap<PathBuf, SystemTime>,
+    after: &BTreeMap<PathBuf, SystemTime>,
+) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    changed
+}
+
+/**
+ * 本地 cargo 的命令，在仓库根目录下运行
+ * 通过 cargo run 启动时使用同一个 cargo，编译的章节(feature)与当前程序相同
+ */
+fn cargo(root: &Path, subcommand: &str) -> Command {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let ids: Vec<&str> = lesson::registry().iter().map(|l| l.id()).collect();
+    let mut command = Command::new(cargo);
+    command
+        .current_dir(root)
+        .args([subcommand, "--quiet", "--no-default-features"])
+        .args(["--features", &ids.join(",")]);
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    command
+}
+
+// 编译错误由 cargo 直接输出到终端
+fn build(root: &Path) -> bool {
+    cargo(root, "build")
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+fn rerun(root: &Path, ids: &[&str]) {
+    let lang = match i18n::locale() {
+        Locale::Zh => "zh",
+        Locale::En => "en",
+    };
+    // 日志的过滤规则也原样传给新编译出来的程序
+    let filter = log::filter();
+    let mut command = cargo(root, "run");
+    command
+        .args(["--", "--lang", lang, "--log", &filter.spec()])
+        .args(filter.timestamps().then_some("--timestamps"))
+        .args(["run", "--no-input"])
+        .args(ids);
+    // 章节失败时程序返回非 0，失败的信息已经在输出中了
+    if let Err(e) = command.status() {
+        eprintln!("{}", tr!("cli.error", e));
+    }
+}
@@ -0,0 +1,107 @@
+// 从库的外部使用公开的 API: 章节注册表、Lesson、Output、Input 和各章节导出的类型
+
+use rust_learn::{outln, registry, Input, Lesson, Output, Section};
+
+// 运行章节，返回全部输出和每个课程函数的名字
+fn run(lesson: &dyn Lesson, input: &mut Input) -> (String, Vec<&'static str>) {
+    let mut buf = Vec::new();
+    let mut names = Vec::new();
+    lesson.run_with(
+        &mut Output::new(&mut buf),
+        input,
+        &mut |section: &Section, out: &mut Output, input: &mut Input| {
+            names.push(section.name);
+            (section.run)(out, input);
+        },
+    );
+    (String::from_utf8(buf).unwrap(), names)
+}
+
+#[test]
+fn registry_is_in_course_order() {
+    let ids: Vec<&str> = registry().iter().map(|lesson| lesson.id()).collect();
+    let mut sorted = ids.clone();
+    sorted.sort_unstable();
+    assert_eq!(ids, sorted);
+    for lesson in registry() {
+        assert!(!lesson.title().is_empty(), "{}", lesson.id());
+        assert!(!lesson.sections().is_empty(), "{}", lesson.id());
+    }
+}
+
+#[cfg(feature = "base_07")]
+#[test]
+fn run_with_calls_every_section_between_the_banners() {
+    let lesson = registry()
+        .iter()
+        .find(|lesson| lesson.id() == "base_07")
+        .unwrap();
+    let (output, names) = run(*lesson, &mut Input::Defaults);
+
+    let expected: Vec<&str> = lesson.sections().iter().map(|s| s.name).collect();
+    assert_eq!(names, expected);
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines.first().unwrap().contains("base_07"), "{}", output);
+    assert_eq!(lines.first(), lines.last());
+    assert!(output.contains("area:"), "{}", output);
+}
+
+// 库外面定义的章节，使用 run_with 的默认实现
+struct Greeting;
+
+const SECTIONS: &[Section] = &[
+    Section::new("hello", |out, _| outln!(out, "hello")),
+    Section::new("echo", |out, input| {
+        let mut line = String::new();
+        input.read_line(&mut line, "nobody").unwrap();
+        outln!(out, "echo {}", line.trim());
+    }),
+];
+
+impl Lesson for Greeting {
+    fn id(&self) -> &'static str {
+        "greeting"
+    }
+
+    fn title(&self) -> &'static str {
+        "问候 greeting"
+    }
+
+    fn topics(&self) -> &'static [&'static str] {
+        &["output", "input"]
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        SECTIONS
+    }
+}
+
+#[test]
+fn lessons_can_be_defined_outside_the_crate() {
+    let (output, names) = run(&Greeting, &mut Input::scripted(["ferris"]));
+    assert_eq!(output, "hello\necho ferris\n");
+    assert_eq!(names, ["hello", "echo"]);
+
+    let (output, _) = run(&Greeting, &mut Input::Defaults);
+    assert_eq!(output, "hello\necho nobody\n");
+}
+
+#[cfg(feature = "base_07")]
+#[test]
+fn circle_area() {
+    let circle = rust_learn::Circle::new(0.0, 0.0, 2.0);
+    assert!((circle.area() - 4.0 * std::f64::consts::PI).abs() < 1e-9);
+}
+
+#[cfg(feature = "base_08")]
+#[test]
+fn largest_finds_the_maximum() {
+    assert_eq!(rust_learn::largest(&[3, 7, 2]), 7);
+    assert_eq!(rust_learn::largest_t(&["b", "c", "a"]), &"c");
+}
+
+#[cfg(feature = "base_10")]
+#[test]
+fn longest_returns_the_first_argument() {
+    assert_eq!(rust_learn::longest("abc", "de"), "abc");
+}